@@ -0,0 +1,359 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2024 Taylan Gökkaya
+
+// This file is licensed under the terms of Apache-2.0 License.
+
+use std::fmt;
+
+use crate::{
+	ast::{
+		self,
+		Capture,
+		Pattern,
+		Segment,
+	},
+	engine::priority,
+	errors::Location,
+	parser::Quantifier,
+	Command,
+};
+
+/// Two captures that can match the same input, so which one gets a value
+/// depends on the order they are tried in.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Ambiguity {
+	/// The capture that is tried first and takes the input.
+	pub winner: String,
+	/// The capture that only gets what `winner` leaves.
+	pub loser: String,
+	/// True if the captures are in the same group, false if `winner` is right
+	/// before `loser`.
+	pub same_group: bool,
+	/// Where the segment containing `loser` is in the source, if the command
+	/// was compiled from source.
+	pub location: Option<Location>,
+}
+
+/// The result of [Command::ambiguities].
+#[derive(Clone, Eq, PartialEq, Debug, Default)]
+pub struct AmbiguityReport(pub Vec<Ambiguity>);
+
+impl AmbiguityReport {
+	/// Returns true if no ambiguities were found.
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+
+	/// Returns an iterator over the ambiguities.
+	pub fn iter(&self) -> std::slice::Iter<'_, Ambiguity> {
+		self.0.iter()
+	}
+}
+
+impl Command {
+	/// Finds captures whose patterns overlap with a neighbouring capture.
+	///
+	/// This goes further than the check done by [Command::new], which only
+	/// rejects two captures that accept any word next to each other. Patterns
+	/// are assumed to overlap unless they provably can not; regexes are only
+	/// told apart by the literal text they are anchored to, such as `^abc` or
+	/// `abc$`.
+	/// #### Examples
+	/// ```rust
+	/// let cmd = malachi::Command::new("?rm <short*: starts('-')> <long+: starts('--')>")?;
+	/// let report = cmd.ambiguities();
+	///
+	/// assert_eq!(report.0.len(), 1);
+	/// assert_eq!(report.0[0].winner, "short");
+	/// assert_eq!(report.0[0].loser, "long");
+	/// # Ok::<(), malachi::Error>(())
+	/// ```
+	pub fn ambiguities(&self) -> AmbiguityReport {
+		let mut found = Vec::new();
+		let locations = self.locations();
+		let location = |i: usize| locations.get(i).cloned().flatten();
+
+		for (i, seg) in self.segments.iter().enumerate() {
+			match seg {
+				Segment::Group(cs) => {
+					let mut cs = cs.iter().collect::<Vec<_>>();
+					cs.sort_by_key(|c| priority(c.quantifier, !c.patterns.is_empty()));
+					group_ambiguities(&cs, location(i), &mut found);
+				}
+				Segment::PriorityGroup(cs) => {
+					let cs = cs.iter().collect::<Vec<_>>();
+					group_ambiguities(&cs, location(i), &mut found);
+				}
+				_ => (),
+			}
+
+			let Some(next) = self.segments.get(i + 1) else {
+				continue;
+			};
+			for left in seg.captures() {
+				if left.quantifier == Quantifier::Once {
+					continue;
+				}
+				for right in next.captures() {
+					if left.overlaps(right) {
+						found.push(Ambiguity {
+							winner: left.name.clone(),
+							loser: right.name.clone(),
+							same_group: false,
+							location: location(i + 1),
+						});
+					}
+				}
+			}
+		}
+
+		AmbiguityReport(found)
+	}
+}
+
+/// Finds overlapping captures in a group, given in the order they're tried.
+fn group_ambiguities(cs: &[&Capture], location: Option<Location>, found: &mut Vec<Ambiguity>) {
+	for (i, winner) in cs.iter().enumerate() {
+		for loser in &cs[i + 1..] {
+			if winner.overlaps(loser) {
+				found.push(Ambiguity {
+					winner: winner.name.clone(),
+					loser: loser.name.clone(),
+					same_group: true,
+					location: location.clone(),
+				});
+			}
+		}
+	}
+}
+
+/// A capture without patterns matches like a word without a regex.
+static ANY_WORD: Pattern = Pattern::Word { reg: Vec::new() };
+
+impl Capture {
+	fn overlaps(&self, other: &Self) -> bool {
+		let theirs = other.effective_patterns();
+		self.effective_patterns()
+			.into_iter()
+			.any(|a| theirs.iter().any(|b| a.overlaps(b)))
+	}
+
+	fn effective_patterns(&self) -> Vec<&Pattern> {
+		if self.patterns.is_empty() {
+			vec![&ANY_WORD]
+		} else {
+			self.patterns.iter().collect()
+		}
+	}
+}
+
+impl Pattern {
+	/// Returns true if `self` and `other` can match the same input.
+	///
+	/// Patterns are assumed to overlap unless they provably can not.
+	fn overlaps(&self, other: &Self) -> bool {
+		type P = Pattern;
+		match (self, other) {
+			(P::Word { reg } | P::Extract { reg }, P::Eq { any_of, .. })
+			| (P::Eq { any_of, .. }, P::Word { reg } | P::Extract { reg })
+				if !reg.is_empty() =>
+			{
+				reg.iter().any(|r| any_of.iter().any(|s| r.is_match(s)))
+			}
+			(P::CodeBlock { langs: a, .. }, P::CodeBlock { langs: b, .. }) => {
+				a.is_empty() || b.is_empty() || a.iter().any(|x| b.contains(x))
+			}
+			_ => {
+				let disjoint_starts = match (self.starts(), other.starts()) {
+					(Some((a, a_no_case)), Some((b, b_no_case))) => {
+						!prefixes(&a, &b, a_no_case || b_no_case)
+					}
+					_ => false,
+				};
+				let disjoint_ends = match (self.word_ends(), other.word_ends()) {
+					(Some(a), Some(b)) => !suffixes(&a, &b),
+					_ => false,
+				};
+				!disjoint_starts && !disjoint_ends
+			}
+		}
+	}
+
+	/// Returns the texts a match must start with one of, and whether they are
+	/// compared case insensitively; `None` if a match can start with anything.
+	fn starts(&self) -> Option<(Vec<String>, bool)> {
+		match self {
+			Self::Eq { any_of, no_case } => Some((any_of.clone(), *no_case)),
+			// With only `ends`, a match can start with anything.
+			Self::Delimited { starts, no_case, .. } if !starts.is_empty() => {
+				Some((starts.clone(), *no_case))
+			}
+			Self::Delimited { .. } => None,
+			Self::CodeBlock { .. } => Some((vec![String::from("`")], false)),
+			Self::Word { reg } => {
+				literals(reg, |r| RegexLiterals::of(r.as_str()).prefix).map(|xs| (xs, false))
+			}
+			// Extract regexes always match at the start.
+			Self::Extract { reg } => literals(reg, |r| {
+				let src = ast::unanchor(r);
+				RegexLiterals::of(&format!("^{}", src.strip_prefix('^').unwrap_or(src))).prefix
+			})
+			.map(|xs| (xs, false)),
+		}
+	}
+
+	/// Returns the texts a match that ends at the end of a word must end with
+	/// one of; `None` if it can end with anything or does not end with the
+	/// word.
+	fn word_ends(&self) -> Option<Vec<String>> {
+		match self {
+			Self::Word { reg } => literals(reg, |r| RegexLiterals::of(r.as_str()).suffix),
+			// The regex validates the rest of the word after the prefix.
+			Self::Delimited { starts, ends, reg, .. } if !starts.is_empty() && ends.is_empty() => {
+				literals(reg, |r| RegexLiterals::of(r.as_str()).suffix)
+			}
+			_ => None,
+		}
+	}
+}
+
+/// Returns the literal found by `f` in each regex of `reg`, or `None` if any
+/// of them has no literal.
+fn literals(reg: &[regex::Regex], f: impl Fn(&regex::Regex) -> String) -> Option<Vec<String>> {
+	if reg.is_empty() {
+		return None;
+	}
+	reg.iter()
+		.map(|r| Some(f(r)).filter(|s| !s.is_empty()))
+		.collect()
+}
+
+/// The literal text a regex must match at the start and the end of its input,
+/// if it is anchored with `^` or `$`.
+///
+/// This is a plain scan of the source, so anything it can not be sure about
+/// (alternations, inline flags) results in empty literals.
+#[derive(Debug, Default)]
+struct RegexLiterals {
+	prefix: String,
+	suffix: String,
+}
+
+/// A part of a regex, as far as [RegexLiterals] is concerned.
+#[derive(Clone, Copy, PartialEq)]
+enum Token {
+	Start,
+	End,
+	Literal(char),
+	Other,
+}
+
+impl RegexLiterals {
+	fn of(src: &str) -> Self {
+		if src.contains('|') || src.contains("(?") {
+			return Self::default();
+		}
+
+		let mut tokens = Vec::new();
+		let mut chars = src.chars();
+		while let Some(c) = chars.next() {
+			let token = match c {
+				'\\' => match chars.next() {
+					Some(c) if c.is_ascii_punctuation() => Token::Literal(c),
+					_ => Token::Other,
+				},
+				'^' => Token::Start,
+				'$' => Token::End,
+				// A repeated or optional character is not literal.
+				'*' | '+' | '?' | '{' => {
+					if let Some(last) = tokens.last_mut() {
+						*last = Token::Other;
+					}
+					Token::Other
+				}
+				'.' | '(' | ')' | '[' | ']' | '}' => Token::Other,
+				c => Token::Literal(c),
+			};
+			tokens.push(token);
+		}
+
+		let literal = |t: &Token| match t {
+			Token::Literal(c) => Some(*c),
+			_ => None,
+		};
+		let mut lits = Self::default();
+		if tokens.first() == Some(&Token::Start) {
+			lits.prefix = tokens[1..].iter().map_while(literal).collect();
+		}
+		if tokens.last() == Some(&Token::End) {
+			let mut suffix: Vec<char> = tokens[..tokens.len() - 1]
+				.iter()
+				.rev()
+				.map_while(literal)
+				.collect();
+			suffix.reverse();
+			lits.suffix = suffix.into_iter().collect();
+		}
+		lits
+	}
+}
+
+/// Returns true if any string in `a` is a prefix of any string in `b`, or the
+/// other way around.
+fn prefixes(a: &[String], b: &[String], no_case: bool) -> bool {
+	let fold = |s: &String| {
+		if no_case {
+			s.to_lowercase()
+		} else {
+			s.clone()
+		}
+	};
+	a.iter().map(fold).any(|a| {
+		b.iter()
+			.map(fold)
+			.any(|b| a.starts_with(&b) || b.starts_with(&a))
+	})
+}
+
+/// Returns true if any string in `a` is a suffix of any string in `b`, or the
+/// other way around.
+fn suffixes(a: &[String], b: &[String]) -> bool {
+	a.iter()
+		.any(|a| b.iter().any(|b| a.ends_with(b.as_str()) || b.ends_with(a.as_str())))
+}
+
+impl fmt::Display for Ambiguity {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		if let Some(loc) = &self.location {
+			write!(f, "{}: ", loc)?;
+		}
+		if self.same_group {
+			write!(
+				f,
+				"`{}` and `{}` can match the same input; `{}` is tried first and takes it",
+				self.winner, self.loser, self.winner
+			)
+		} else {
+			write!(
+				f,
+				"`{}` and `{}` can match the same input; `{}` takes it unless `{}` needs it for the command to match",
+				self.winner, self.loser, self.winner, self.loser
+			)
+		}
+	}
+}
+
+impl fmt::Display for AmbiguityReport {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		if self.is_empty() {
+			return f.write_str("no ambiguities found");
+		}
+		for (i, a) in self.0.iter().enumerate() {
+			if i > 0 {
+				f.write_str("\n")?;
+			}
+			write!(f, "{}", a)?;
+		}
+		Ok(())
+	}
+}
@@ -0,0 +1,342 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2024 Taylan Gökkaya
+
+pub mod arg_match;
+mod owned;
+use std::{
+	borrow::Cow,
+	collections::HashMap,
+	ops::Range,
+};
+
+use indexmap::IndexMap;
+
+use crate::engine::Split;
+pub use owned::{
+	OwnedArgs,
+	OwnedGroups,
+	OwnedMatch,
+};
+
+/// Represents a capture from a text.
+///
+/// The lifetime `'a` refers to the match text.
+#[derive(Eq, PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(untagged))]
+pub enum Match<'a> {
+	/// Used whenever a capture matches and has no quantifier or the `?`
+	/// quantifier.
+	Once(&'a str),
+	/// Used when a capture has at least 1 matches and has the `*` or the `+`
+	/// quantifiers.
+	Many(Vec<&'a str>),
+}
+
+/// Named parts of a single captured value, such as the language of a code
+/// block or the groups of a regular expression.
+///
+/// For regular expressions, parts are the capture groups of the first regex
+/// that matched the value; regexes without any capture groups produce no
+/// parts. For code blocks, the part at index `0` is the whole block including
+/// the delimiters and is followed by any regex groups, keeping their numbers;
+/// the language is the `lang` part, which can only be looked up by name.
+///
+/// Lifetime `'c` refers to the command and `'t` refers to the text that was
+/// matched.
+#[derive(Eq, PartialEq, Clone, Debug, Default, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize), serde(transparent))]
+pub struct Groups<'c, 't>(
+	pub(crate) Vec<(Option<&'c str>, Option<&'t str>)>,
+	// The number of parts at the end that have no index.
+	#[cfg_attr(feature = "serde", serde(skip))] pub(crate) usize,
+);
+
+#[doc = include_str!("docs/args.md")]
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
+pub struct Args<'c, 't> {
+	/// The trailing part of the text that was not captured by any `capture`.
+	///
+	/// Note that no whitespace is trimmed.
+	pub rest: &'t str,
+	#[cfg_attr(feature = "serde", serde(rename = "captures"))]
+	// Kept sorted by the position of the first value of each capture.
+	pub(crate) vals: IndexMap<&'c str, Match<'t>>,
+	// Only captures with at least one non-empty `Groups` are stored.
+	#[cfg_attr(feature = "serde", serde(skip_serializing_if = "HashMap::is_empty"))]
+	pub(crate) groups: HashMap<&'c str, Vec<Groups<'c, 't>>>,
+	// The byte ranges of every captured value in the input, in the same order
+	// as the values.
+	#[cfg_attr(feature = "serde", serde(skip))]
+	pub(crate) spans: HashMap<&'c str, Vec<Range<usize>>>,
+	// The escapes of the patterns that matched each value, in the same order
+	// as the values. Only captures with at least one escape are stored.
+	#[cfg_attr(feature = "serde", serde(skip))]
+	pub(crate) escapes: HashMap<&'c str, Vec<&'c [String]>>,
+}
+
+impl<'c, 't> Groups<'c, 't> {
+	/// Returns the part with the name `name`.
+	///
+	/// If `name` is a number, the part at that index is returned instead.
+	pub fn get(&self, name: &str) -> Option<&'t str> {
+		match name.parse::<usize>() {
+			Ok(i) => self.index(i),
+			Err(_) => self.name(name),
+		}
+	}
+
+	/// Returns the part with the name `name`.
+	pub fn name(&self, name: &str) -> Option<&'t str> {
+		self.0
+			.iter()
+			.find(|(k, _)| *k == Some(name))
+			.and_then(|(_, v)| *v)
+	}
+
+	/// Returns the part at index `i`.
+	pub fn index(&self, i: usize) -> Option<&'t str> {
+		self.0[..self.0.len() - self.1]
+			.get(i)
+			.and_then(|(_, v)| *v)
+	}
+
+	/// Returns `true` if there are no parts.
+	pub fn is_empty(&self) -> bool {
+		self.0.is_empty()
+	}
+
+	/// Returns an iterator over the parts, in order, with their names if any.
+	pub fn iter(&self) -> impl Iterator<Item = (Option<&'c str>, Option<&'t str>)> + '_ {
+		self.0.iter().copied()
+	}
+}
+
+impl<'c, 't, 'z: 'c + 't> Args<'c, 't> {
+	/// Returns `Some(Match)` if the capture with the name `name` has matched.
+	pub fn get(&'z self, name: &str) -> Option<&'z Match<'t>> {
+		self.vals.get(name)
+	}
+
+	/// Returns `Some(&str)` if the `name` has matched and is a capture that
+	/// matches at most once (no quantifier or the `?` quantifier).
+	pub fn get_once(&'z self, key: &str) -> Option<&'z str> {
+		self.vals.get(key).and_then(|m| match *m {
+			Match::Once(s) => Some(s),
+			_ => None,
+		})
+	}
+
+	/// Returns `Some(&Vec)` if the `name` has matched and is a capture that can
+	/// match multiple times (quantifiers `*` and `+`).
+	pub fn get_many(&'z self, key: &str) -> Option<&'z Vec<&'z str>> {
+		self.vals.get(key).and_then(|m| match m {
+			Match::Many(xs) => Some(xs),
+			_ => None,
+		})
+	}
+
+	/// Takes the underlying map from this match.
+	///
+	/// Captures are ordered by the position of their first value in the input.
+	pub fn into_matches(self) -> IndexMap<&'c str, Match<'t>> {
+		self.vals
+	}
+
+	/// Returns an iterator over every captured value with the name of its
+	/// capture and its byte range, in the order the values appear in the input.
+	/// #### Examples
+	/// ```rust
+	/// let cmd = malachi::Command::new("?run {<v*: eq('-v')> <fast?: eq('-f')>}")?;
+	/// let args = cmd.get_matches("?run -v -f -v").unwrap();
+	///
+	/// let order: Vec<_> = args.iter().map(|(name, val, _)| (name, val)).collect();
+	/// assert_eq!(vec![("v", "-v"), ("fast", "-f"), ("v", "-v")], order);
+	/// # Ok::<(), malachi::Error>(())
+	/// ```
+	pub fn iter(&self) -> impl Iterator<Item = (&'c str, &'t str, Range<usize>)> + '_ {
+		let mut items = Vec::new();
+		for (&name, m) in &self.vals {
+			let vals = match m {
+				Match::Once(s) => std::slice::from_ref(s),
+				Match::Many(xs) => xs.as_slice(),
+			};
+			let spans = self.spans.get(name).map_or(&[][..], |xs| xs.as_slice());
+			items.extend(
+				vals.iter()
+					.zip(spans)
+					.map(|(&val, span)| (name, val, span.clone())),
+			);
+		}
+
+		items.sort_by_key(|(_, _, span)| span.start);
+		items.into_iter()
+	}
+
+	/// Returns the part `group` of the first value captured by `name`.
+	///
+	/// See [Groups::get] for what `group` can be.
+	/// #### Examples
+	/// ```rust
+	/// let cmd = malachi::Command::new("?eval <code: codeblock()>")?;
+	/// let args = cmd.get_matches("?eval ```rust\nfn main() {}```").unwrap();
+	///
+	/// assert_eq!(Some("fn main() {}"), args.get_once("code"));
+	/// assert_eq!(Some("rust"), args.group("code", "lang"));
+	/// # Ok::<(), malachi::Error>(())
+	/// ```
+	pub fn group(&self, name: &str, group: &str) -> Option<&'t str> {
+		self.groups
+			.get(name)
+			.and_then(|gs| gs.first())
+			.and_then(|g| g.get(group))
+	}
+
+	/// Returns the [Groups] of every value captured by `name`, in the same
+	/// order as the values.
+	///
+	/// Returns `None` if `name` did not match or its patterns do not produce
+	/// any groups.
+	pub fn groups(&self, name: &str) -> Option<&[Groups<'c, 't>]> {
+		self.groups.get(name).map(|gs| gs.as_slice())
+	}
+
+	/// Returns every value captured by `name` with the escape sequences of the
+	/// `escape` filter removed.
+	///
+	/// An escape sequence is removed and the character after it is kept, so
+	/// `\\` becomes `\`. Values of patterns without the filter are returned as
+	/// is.
+	/// #### Examples
+	/// ```rust
+	/// let cmd = malachi::Command::new(r#"?say <msg: starts('"'), ends('"'), escape("\\")>"#)?;
+	/// let args = cmd.get_matches(r#"?say "a \"quoted\" \\ word""#).unwrap();
+	///
+	/// assert_eq!(Some(r#"a \"quoted\" \\ word"#), args.get_once("msg"));
+	/// assert_eq!(Some(vec![r#"a "quoted" \ word"#.into()]), args.unescaped("msg"));
+	/// # Ok::<(), malachi::Error>(())
+	/// ```
+	pub fn unescaped(&self, name: &str) -> Option<Vec<Cow<'t, str>>> {
+		let vals = match self.vals.get(name)? {
+			Match::Once(s) => std::slice::from_ref(s),
+			Match::Many(xs) => xs.as_slice(),
+		};
+		let vals = match self.escapes.get(name) {
+			Some(escapes) => vals
+				.iter()
+				.zip(escapes)
+				.map(|(s, escapes)| unescape(s, escapes))
+				.collect(),
+			None => vals.iter().map(|&s| Cow::Borrowed(s)).collect(),
+		};
+		Some(vals)
+	}
+
+	/// Returns the byte range in the input of the first value captured by
+	/// `name`.
+	/// #### Examples
+	/// ```rust
+	/// let cmd = malachi::Command::new("?ban <user> <reason*>")?;
+	/// let input = "?ban bob   for spam";
+	/// let args = cmd.get_matches(input).unwrap();
+	///
+	/// assert_eq!(Some(5..8), args.span("user"));
+	/// let spans = args.spans("reason").unwrap();
+	/// assert_eq!("spam", &input[spans[1].clone()]);
+	/// # Ok::<(), malachi::Error>(())
+	/// ```
+	pub fn span(&self, name: &str) -> Option<Range<usize>> {
+		self.spans.get(name).and_then(|xs| xs.first()).cloned()
+	}
+
+	/// Returns the byte ranges in the input of every value captured by `name`,
+	/// in the same order as the values.
+	pub fn spans(&self, name: &str) -> Option<&[Range<usize>]> {
+		self.spans.get(name).map(|xs| xs.as_slice())
+	}
+
+	/// Returns `true` if `name` has any matches.
+	pub fn is_present(&self, name: &str) -> bool {
+		self.get(name).is_some()
+	}
+
+	pub fn take(&mut self, name: &str) -> Option<Match<'t>> {
+		self.groups.remove(name);
+		self.escapes.remove(name);
+		self.spans.remove(name);
+		self.vals.shift_remove(name)
+	}
+
+	pub fn take_many(&mut self, name: &str) -> Option<Vec<&'t str>> {
+		if self.get_many(name).is_some() {
+			self.take(name).and_then(|m| m.many())
+		} else {
+			None
+		}
+	}
+}
+
+/// Removes the escape sequences in `s`, keeping the characters they escape.
+fn unescape<'a>(s: &'a str, escapes: &[String]) -> Cow<'a, str> {
+	let escape_at = |i: usize| {
+		escapes
+			.iter()
+			.find(|e| !e.is_empty() && s[i..].starts_with(e.as_str()))
+	};
+	if !s.char_indices().any(|(i, _)| escape_at(i).is_some()) {
+		return Cow::Borrowed(s);
+	}
+
+	let mut out = String::with_capacity(s.len());
+	let mut i = 0;
+	while i < s.len() {
+		if let Some(esc) = escape_at(i) {
+			// Drop the escape and keep the character it escapes.
+			i += esc.len();
+		}
+		if let Some(c) = s[i..].chars().next() {
+			out.push(c);
+			i += c.len_utf8();
+		}
+	}
+	Cow::Owned(out)
+}
+
+impl<'c, 't> Args<'c, 't> {
+	pub(crate) fn insert(&mut self, name: &'c str, split: Split<'c, 't>) {
+		let Split {
+			m,
+			groups,
+			spans,
+			escapes,
+		} = split;
+		if groups.iter().any(|g| !g.is_empty()) {
+			self.groups.insert(name, groups);
+		} else {
+			self.groups.remove(name);
+		}
+		if escapes.iter().any(|e| !e.is_empty()) {
+			self.escapes.insert(name, escapes);
+		} else {
+			self.escapes.remove(name);
+		}
+		self.spans.insert(name, spans);
+		self.vals.insert(name, m);
+	}
+
+	/// Moves the spans of the values `by` bytes forward, for when the matched
+	/// text started at that offset.
+	pub(crate) fn offset_spans(&mut self, by: usize) {
+		for span in self.spans.values_mut().flatten() {
+			span.start += by;
+			span.end += by;
+		}
+	}
+
+	/// Orders the captures by the position of their first values.
+	pub(crate) fn sort(&mut self) {
+		let spans = &self.spans;
+		let start = |name: &str| spans.get(name).and_then(|xs| xs.first()).map(|r| r.start);
+		self.vals.sort_by(|a, _, b, _| start(a).cmp(&start(b)));
+	}
+}
@@ -0,0 +1,267 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2024 Taylan Gökkaya
+
+use std::{
+	borrow::Cow,
+	collections::HashMap,
+	ops::Range,
+};
+
+use indexmap::IndexMap;
+
+use super::{
+	Args,
+	Groups,
+	Match,
+};
+
+/// The owned counterpart of [Match].
+#[derive(Eq, PartialEq, Clone, Debug, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(untagged)
+)]
+pub enum OwnedMatch {
+	/// See [Match::Once].
+	Once(String),
+	/// See [Match::Many].
+	Many(Vec<String>),
+}
+
+/// The owned counterpart of [Groups].
+#[derive(Eq, PartialEq, Clone, Debug, Default, Hash)]
+pub struct OwnedGroups(Vec<(Option<String>, Option<String>)>, usize);
+
+/// Matches that do not borrow from the [Command][crate::Command] or the text,
+/// returned by [Args::into_owned].
+/// #### Examples
+/// ```rust
+/// let cmd = malachi::Command::new("?remind <when> <what+>")?;
+/// let args = {
+/// 	let msg = String::from("?remind 5m take a break");
+/// 	cmd.get_matches(&msg).unwrap().into_owned()
+/// };
+///
+/// assert_eq!(Some("5m"), args.get_once("when"));
+/// assert_eq!(Some(8..10), args.span("when"));
+/// # Ok::<(), malachi::Error>(())
+/// ```
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct OwnedArgs {
+	/// See [Args::rest].
+	pub rest: String,
+	#[cfg_attr(feature = "serde", serde(rename = "captures"))]
+	vals: IndexMap<String, OwnedMatch>,
+	#[cfg_attr(feature = "serde", serde(skip))]
+	groups: HashMap<String, Vec<OwnedGroups>>,
+	#[cfg_attr(feature = "serde", serde(skip))]
+	spans: HashMap<String, Vec<Range<usize>>>,
+	// The unescaped values of the captures in `Args::escapes`.
+	#[cfg_attr(feature = "serde", serde(skip))]
+	unescaped: HashMap<String, Vec<String>>,
+}
+
+impl OwnedMatch {
+	/// Returns `Some(vals)` if `self` is `Self::Many`, else returns `None`.
+	pub fn many(self) -> Option<Vec<String>> {
+		match self {
+			Self::Many(v) => Some(v),
+			Self::Once(_) => None,
+		}
+	}
+
+	/// Returns `Some(s)` if `self` is `Self::Once`, else returns `None`.
+	pub fn once(self) -> Option<String> {
+		match self {
+			Self::Once(s) => Some(s),
+			Self::Many(_) => None,
+		}
+	}
+
+	/// Borrows `self` as a [Match].
+	pub fn as_match(&self) -> Match<'_> {
+		match self {
+			Self::Once(s) => Match::Once(s),
+			Self::Many(xs) => Match::Many(xs.iter().map(String::as_str).collect()),
+		}
+	}
+}
+
+impl From<Match<'_>> for OwnedMatch {
+	fn from(m: Match) -> Self {
+		match m {
+			Match::Once(s) => Self::Once(s.to_string()),
+			Match::Many(xs) => Self::Many(xs.into_iter().map(String::from).collect()),
+		}
+	}
+}
+
+impl OwnedGroups {
+	/// See [Groups::get].
+	pub fn get(&self, name: &str) -> Option<&str> {
+		self.as_groups().get(name)
+	}
+
+	/// Borrows `self` as [Groups].
+	pub fn as_groups(&self) -> Groups<'_, '_> {
+		Groups(
+			self.0
+				.iter()
+				.map(|(k, v)| (k.as_deref(), v.as_deref()))
+				.collect(),
+			self.1,
+		)
+	}
+}
+
+impl From<Groups<'_, '_>> for OwnedGroups {
+	fn from(g: Groups) -> Self {
+		Self(
+			g.0.into_iter()
+				.map(|(k, v)| (k.map(String::from), v.map(String::from)))
+				.collect(),
+			g.1,
+		)
+	}
+}
+
+impl Args<'_, '_> {
+	/// Copies the matches into an [OwnedArgs], which can outlive the command
+	/// and the text.
+	pub fn into_owned(self) -> OwnedArgs {
+		let unescaped = self
+			.escapes
+			.keys()
+			.filter_map(|&k| {
+				let vals = self.unescaped(k)?;
+				Some((k.to_string(), vals.into_iter().map(Cow::into_owned).collect()))
+			})
+			.collect();
+		OwnedArgs {
+			rest: self.rest.to_string(),
+			vals: self
+				.vals
+				.into_iter()
+				.map(|(k, v)| (k.to_string(), OwnedMatch::from(v)))
+				.collect(),
+			groups: self
+				.groups
+				.into_iter()
+				.map(|(k, gs)| (k.to_string(), gs.into_iter().map(OwnedGroups::from).collect()))
+				.collect(),
+			spans: self
+				.spans
+				.into_iter()
+				.map(|(k, v)| (k.to_string(), v))
+				.collect(),
+			unescaped,
+		}
+	}
+}
+
+impl OwnedArgs {
+	/// See [Args::get].
+	pub fn get(&self, name: &str) -> Option<&OwnedMatch> {
+		self.vals.get(name)
+	}
+
+	/// See [Args::get_once].
+	pub fn get_once(&self, name: &str) -> Option<&str> {
+		self.vals.get(name).and_then(|m| match m {
+			OwnedMatch::Once(s) => Some(s.as_str()),
+			_ => None,
+		})
+	}
+
+	/// See [Args::get_many].
+	pub fn get_many(&self, name: &str) -> Option<&Vec<String>> {
+		self.vals.get(name).and_then(|m| match m {
+			OwnedMatch::Many(xs) => Some(xs),
+			_ => None,
+		})
+	}
+
+	/// See [Args::is_present].
+	pub fn is_present(&self, name: &str) -> bool {
+		self.vals.contains_key(name)
+	}
+
+	/// See [Args::iter].
+	pub fn iter(&self) -> impl Iterator<Item = (&str, &str, Range<usize>)> + '_ {
+		let mut items = Vec::new();
+		for (name, m) in &self.vals {
+			let vals = match m {
+				OwnedMatch::Once(s) => std::slice::from_ref(s),
+				OwnedMatch::Many(xs) => xs.as_slice(),
+			};
+			let spans = self.spans.get(name).map_or(&[][..], |xs| xs.as_slice());
+			items.extend(
+				vals.iter()
+					.zip(spans)
+					.map(|(val, span)| (name.as_str(), val.as_str(), span.clone())),
+			);
+		}
+
+		items.sort_by_key(|(_, _, span)| span.start);
+		items.into_iter()
+	}
+
+	/// See [Args::group].
+	pub fn group(&self, name: &str, group: &str) -> Option<&str> {
+		self.groups
+			.get(name)
+			.and_then(|gs| gs.first())
+			.and_then(|g| g.get(group))
+	}
+
+	/// See [Args::groups].
+	pub fn groups(&self, name: &str) -> Option<&[OwnedGroups]> {
+		self.groups.get(name).map(|gs| gs.as_slice())
+	}
+
+	/// See [Args::unescaped].
+	pub fn unescaped(&self, name: &str) -> Option<Vec<Cow<'_, str>>> {
+		if let Some(xs) = self.unescaped.get(name) {
+			return Some(xs.iter().map(|s| Cow::Borrowed(s.as_str())).collect());
+		}
+		let vals = match self.vals.get(name)? {
+			OwnedMatch::Once(s) => std::slice::from_ref(s),
+			OwnedMatch::Many(xs) => xs.as_slice(),
+		};
+		Some(vals.iter().map(|s| Cow::Borrowed(s.as_str())).collect())
+	}
+
+	/// See [Args::span].
+	pub fn span(&self, name: &str) -> Option<Range<usize>> {
+		self.spans.get(name).and_then(|xs| xs.first()).cloned()
+	}
+
+	/// See [Args::spans].
+	pub fn spans(&self, name: &str) -> Option<&[Range<usize>]> {
+		self.spans.get(name).map(|xs| xs.as_slice())
+	}
+
+	/// See [Args::take].
+	pub fn take(&mut self, name: &str) -> Option<OwnedMatch> {
+		self.groups.remove(name);
+		self.unescaped.remove(name);
+		self.spans.remove(name);
+		self.vals.shift_remove(name)
+	}
+
+	/// See [Args::take_many].
+	pub fn take_many(&mut self, name: &str) -> Option<Vec<String>> {
+		if self.get_many(name).is_some() {
+			self.take(name).and_then(|m| m.many())
+		} else {
+			None
+		}
+	}
+
+	/// See [Args::into_matches].
+	pub fn into_matches(self) -> IndexMap<String, OwnedMatch> {
+		self.vals
+	}
+}
@@ -0,0 +1,415 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2024 Taylan Gökkaya
+
+use std::fmt::{
+	self,
+	Write,
+};
+
+use regex::Regex;
+
+pub use crate::parser::Quantifier;
+use crate::Limit;
+
+/// A segment in a [Command][crate::Command].
+#[derive(Clone, Debug)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(rename_all = "snake_case")
+)]
+pub enum Segment {
+	/// Literal text, does not capture.
+	Text(String),
+	/// A single capture. E.g. `<foo: starts("bar")>`.
+	Capture(Capture),
+	/// A capture group. E.g. `{<first> <second: "lol">}`.
+	Group(Vec<Capture>),
+	/// A priority group. E.g. `[<first> <second> <third?: "foo">]`.
+	PriorityGroup(Vec<Capture>),
+}
+
+/// Represents a set of rules for the capture to match.
+///
+/// With the `serde` feature, regexes are serialized as their source and
+/// compiled again when deserialized.
+#[derive(Debug, Clone)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(rename_all = "snake_case")
+)]
+pub enum Pattern {
+	/// Corresponds to the `eq()` filter.
+	Eq { any_of: Vec<String>, no_case: bool },
+	/// Represents a pattern with at least one of `starts()` or `ends()`.
+	Delimited {
+		starts: Vec<String>,
+		ends: Vec<String>,
+		#[cfg_attr(feature = "serde", serde(with = "crate::ser::regexes"))]
+		reg: Vec<Regex>,
+		/// This does not affect the regex!
+		no_case: bool,
+		no_trim: bool,
+		/// Corresponds to the `balanced()` filter: nested `starts` and `ends`
+		/// pairs are skipped over instead of ending the match early.
+		balanced: bool,
+		/// Arguments of the `balanced()` filter; delimiters inside these quotes
+		/// are ignored.
+		quotes: Vec<String>,
+		/// Corresponds to the `escape()` filter; an `ends` delimiter preceded by
+		/// any of these does not end the match.
+		escapes: Vec<String>,
+	},
+	/// Represents a capture without any filters. E.. `<foo>`.
+	Word {
+		#[cfg_attr(feature = "serde", serde(with = "crate::ser::regexes"))]
+		reg: Vec<Regex>,
+	},
+	/// Corresponds to the `codeblock()` filter; matches a markdown code block
+	/// or inline code.
+	CodeBlock {
+		/// Allowed languages; any language or none is allowed if empty.
+		langs: Vec<String>,
+		/// Validates the code, not the whole block.
+		#[cfg_attr(feature = "serde", serde(with = "crate::ser::regexes"))]
+		reg: Vec<Regex>,
+		/// Makes language comparison case insensitive.
+		no_case: bool,
+	},
+	/// Corresponds to the `extract()` filter; matches any of the regexes at the
+	/// start of the input, possibly across whitespace.
+	Extract {
+		/// The regexes, anchored to the start of the input.
+		#[cfg_attr(feature = "serde", serde(with = "crate::ser::anchored_regexes"))]
+		reg: Vec<Regex>,
+	},
+}
+
+/// Represents a capturing item with its name in a command.
+#[derive(Clone, Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Capture {
+	/// The name of the capture, later used to get its matches.
+	pub name: String,
+	/// The quantifier, any or none of `*, ?, +`.
+	pub quantifier: Quantifier,
+	/// Any number of patterns this capture will try to match.
+	pub patterns: Vec<Pattern>,
+	/// The text of the `///` doc comments before the capture.
+	#[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+	pub description: Option<String>,
+	/// How many values the capture can match if it has the `*` or the `+`
+	/// quantifier. It is not part of the syntax; see
+	/// [CompileOptions][crate::CompileOptions].
+	#[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Limit::is_default"))]
+	pub limit: Limit,
+}
+
+impl Pattern {
+	pub(crate) fn is_deterministic(&self) -> bool {
+		!matches!(self, Self::Word { reg } if reg.is_empty())
+	}
+}
+
+impl Capture {
+	/// Returns `true` if the capture must match for the command to match; that
+	/// is, it has no quantifier or the `+` quantifier.
+	pub fn is_required(&self) -> bool {
+		matches!(self.quantifier, Quantifier::Once | Quantifier::Many1)
+	}
+
+	/// Returns `true` if the capture can match more than once; that is, it has
+	/// the `*` or the `+` quantifier.
+	pub fn is_many(&self) -> bool {
+		matches!(self.quantifier, Quantifier::Many0 | Quantifier::Many1)
+	}
+
+	/// Returns the only values the capture can match if all its patterns use
+	/// the `eq` filter, `None` otherwise.
+	pub fn choices(&self) -> Option<Vec<&str>> {
+		if self.patterns.is_empty() {
+			return None;
+		}
+		let mut choices = Vec::new();
+		for p in &self.patterns {
+			match p {
+				Pattern::Eq { any_of, .. } => choices.extend(any_of.iter().map(String::as_str)),
+				_ => return None,
+			}
+		}
+		Some(choices)
+	}
+
+	/// Returns the arguments of every `starts` filter of the capture.
+	pub fn prefixes(&self) -> Vec<&str> {
+		self.patterns
+			.iter()
+			.flat_map(|p| match p {
+				Pattern::Delimited { starts, .. } => starts.as_slice(),
+				_ => &[],
+			})
+			.map(String::as_str)
+			.collect()
+	}
+}
+
+// The `Display` implementations below emit valid source that compiles back
+// into an equivalent command, except for capture limits which are not part of
+// the syntax. The alternate form (`{:#}`) spreads groups and captures with more
+// than one pattern over multiple lines.
+
+impl fmt::Display for Segment {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let (open, close, cs) = match self {
+			Self::Text(s) => return write_literal(f, s),
+			Self::Capture(c) => return fmt::Display::fmt(c, f),
+			Self::Group(cs) => ('{', '}', cs),
+			Self::PriorityGroup(cs) => ('[', ']', cs),
+		};
+
+		f.write_char(open)?;
+		if f.alternate() && !cs.is_empty() {
+			for c in cs {
+				// Indent every line of the capture.
+				let s = format!("{:#}", c);
+				for ln in s.lines() {
+					write!(f, "\n\t{}", ln)?;
+				}
+			}
+			f.write_char('\n')?;
+		} else {
+			for (i, c) in cs.iter().enumerate() {
+				if i > 0 {
+					f.write_char(' ')?;
+				}
+				write!(f, "{}", c)?;
+			}
+		}
+		f.write_char(close)
+	}
+}
+
+impl fmt::Display for Capture {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		if let Some(s) = &self.description {
+			write_docs(f, "///", s)?;
+		}
+		write!(f, "<{}{}", self.name, self.quantifier)?;
+		match self.patterns.as_slice() {
+			[] => (),
+			// A lone pattern with only modifiers matches any word, like no
+			// pattern at all.
+			[Pattern::Word { reg }] if reg.is_empty() => (),
+			[p] => write!(f, ": {}", p)?,
+			ps if f.alternate() => {
+				f.write_char(':')?;
+				for p in ps {
+					write!(f, "\n\t{};", p)?;
+				}
+				f.write_char('\n')?;
+			}
+			ps => {
+				f.write_str(": ")?;
+				for (i, p) in ps.iter().enumerate() {
+					if i > 0 {
+						f.write_str("; ")?;
+					}
+					write!(f, "{}", p)?;
+				}
+			}
+		}
+		f.write_char('>')
+	}
+}
+
+impl fmt::Display for Pattern {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let mut filters = Filters(f, 0);
+		match self {
+			Self::Eq { any_of, no_case } => {
+				if *no_case {
+					filters.call("nocase", &[])?;
+				}
+				filters.call("eq", any_of)?;
+			}
+			Self::Delimited {
+				starts,
+				ends,
+				reg,
+				no_case,
+				no_trim,
+				balanced,
+				quotes,
+				escapes,
+			} => {
+				if !starts.is_empty() {
+					filters.call("starts", starts)?;
+				}
+				if !ends.is_empty() {
+					filters.call("ends", ends)?;
+				}
+				if *no_case {
+					filters.call("nocase", &[])?;
+				}
+				if *no_trim {
+					filters.call("notrim", &[])?;
+				}
+				if *balanced {
+					filters.call("balanced", quotes)?;
+				}
+				if !escapes.is_empty() {
+					filters.call("escape", escapes)?;
+				}
+				filters.regexes(reg)?;
+			}
+			// A word pattern without a regex can only come from a pattern with
+			// only modifiers.
+			Self::Word { reg } if reg.is_empty() => filters.call("notrim", &[])?,
+			Self::Word { reg } => filters.regexes(reg)?,
+			Self::CodeBlock {
+				langs,
+				reg,
+				no_case,
+			} => {
+				filters.call("codeblock", langs)?;
+				if *no_case {
+					filters.call("nocase", &[])?;
+				}
+				filters.regexes(reg)?;
+			}
+			Self::Extract { reg } => {
+				filters.next()?;
+				filters.0.write_str("extract(")?;
+				for (i, r) in reg.iter().enumerate() {
+					if i > 0 {
+						filters.0.write_str(", ")?;
+					}
+					write_regex(filters.0, unanchor(r))?;
+				}
+				filters.0.write_char(')')?;
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Returns the source of a regex without the anchor the compiler adds to
+/// `extract` regexes.
+pub(crate) fn unanchor(r: &Regex) -> &str {
+	let s = r.as_str();
+	s.strip_prefix("^(?:")
+		.and_then(|s| s.strip_suffix(')'))
+		.unwrap_or(s)
+}
+
+/// Anchors a regex for the `extract` filter.
+pub(crate) fn anchor(s: &str) -> Result<Regex, regex::Error> {
+	// Check `s` on its own first so it can't close the group, as in `x)|(y`.
+	Regex::new(s)?;
+	Regex::new(&format!("^(?:{})", s))
+}
+
+/// Writes comma separated filters.
+struct Filters<'f, 'a>(&'f mut fmt::Formatter<'a>, usize);
+
+impl Filters<'_, '_> {
+	/// Writes the separator if this is not the first filter.
+	fn next(&mut self) -> fmt::Result {
+		self.1 += 1;
+		if self.1 > 1 {
+			self.0.write_str(", ")?;
+		}
+		Ok(())
+	}
+
+	fn call(&mut self, name: &str, args: &[String]) -> fmt::Result {
+		self.next()?;
+		write!(self.0, "{}(", name)?;
+		for (i, a) in args.iter().enumerate() {
+			if i > 0 {
+				self.0.write_str(", ")?;
+			}
+			write_string(self.0, a)?;
+		}
+		self.0.write_char(')')
+	}
+
+	fn regexes(&mut self, reg: &[Regex]) -> fmt::Result {
+		for r in reg {
+			// `//` is not a valid regex literal.
+			if r.as_str().is_empty() {
+				self.call("regex", &[String::new()])?;
+				continue;
+			}
+			self.next()?;
+			write_regex(self.0, r.as_str())?;
+		}
+		Ok(())
+	}
+}
+
+/// Writes `s` as doc comments starting with `prefix`, followed by a newline.
+pub(crate) fn write_docs(f: &mut fmt::Formatter, prefix: &str, s: &str) -> fmt::Result {
+	for ln in s.split('\n') {
+		if ln.is_empty() {
+			writeln!(f, "{}", prefix)?;
+		} else {
+			writeln!(f, "{} {}", prefix, ln)?;
+		}
+	}
+	Ok(())
+}
+
+/// Writes `s` as an unquoted literal segment.
+fn write_literal(f: &mut fmt::Formatter, s: &str) -> fmt::Result {
+	for (i, c) in s.chars().enumerate() {
+		match c {
+			'\\' => f.write_str("\\\\")?,
+			'\n' => f.write_str("\\n")?,
+			'\r' => f.write_str("\\r")?,
+			'\t' => f.write_str("\\t")?,
+			' ' => f.write_str("\\ ")?,
+			// These would start a capture, a group or a comment.
+			'<' | '[' | '{' if i == 0 => write!(f, "\\{}", c)?,
+			'/' if i == 0 && s.starts_with("//") => f.write_str("\\/")?,
+			c => f.write_char(c)?,
+		}
+	}
+	Ok(())
+}
+
+/// Writes `s` as a quoted string.
+fn write_string(f: &mut fmt::Formatter, s: &str) -> fmt::Result {
+	f.write_char('"')?;
+	for c in s.chars() {
+		match c {
+			'\\' => f.write_str("\\\\")?,
+			'"' => f.write_str("\\\"")?,
+			'\n' => f.write_str("\\n")?,
+			'\r' => f.write_str("\\r")?,
+			'\t' => f.write_str("\\t")?,
+			c => f.write_char(c)?,
+		}
+	}
+	f.write_char('"')
+}
+
+/// Writes `s` as a regex literal, e.g. `/\d+/`.
+fn write_regex(f: &mut fmt::Formatter, s: &str) -> fmt::Result {
+	f.write_char('/')?;
+	let mut chars = s.chars();
+	while let Some(c) = chars.next() {
+		match c {
+			// Escape sequences are kept as is, except for `\/` which the parser
+			// reads as `/`; the regex crate treats them the same way.
+			'\\' => match chars.next() {
+				Some(next) => write!(f, "\\{}", next)?,
+				None => f.write_char('\\')?,
+			},
+			'/' => f.write_str("\\/")?,
+			c => f.write_char(c)?,
+		}
+	}
+	f.write_char('/')
+}
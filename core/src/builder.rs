@@ -0,0 +1,299 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2024 Taylan Gökkaya
+
+// This file is licensed under the terms of Apache-2.0 License.
+
+//! Types for building a [Command] without writing its source.
+//!
+//! Commands built this way go through the same validation as [Command::new],
+//! and strings passed to the builder never need escaping.
+//! #### Examples
+//! ```rust
+//! use malachi::{
+//! 	builder::{
+//! 		Capture,
+//! 		Pattern,
+//! 	},
+//! 	ast::Quantifier,
+//! 	Command,
+//! };
+//!
+//! let cmd = Command::builder()
+//! 	.text("?tag")
+//! 	.capture(Capture::new("name").pattern(Pattern::new().starts(["<"]).ends([">"])))
+//! 	.capture(Capture::new("rest").quantifier(Quantifier::Many0))
+//! 	.build()?;
+//!
+//! let args = cmd.get_matches("?tag <x> y z").unwrap();
+//! assert_eq!(args.get_once("name"), Some("x"));
+//! # Ok::<(), malachi::Error>(())
+//! ```
+
+use crate::{
+	compiler::{
+		check_names,
+		lower,
+	},
+	parser::{
+		self,
+		Quantifier,
+	},
+	Command,
+	Limit,
+};
+
+/// Builds a [Command]; returned by [Command::builder].
+#[derive(Clone, Debug, Default)]
+pub struct Builder {
+	segments: Vec<Segment>,
+	description: Option<String>,
+}
+
+#[derive(Clone, Debug)]
+enum Segment {
+	Text(String),
+	Capture(Capture),
+	Group(Vec<Capture>),
+	PriorityGroup(Vec<Capture>),
+}
+
+/// A capture in a [Builder].
+#[derive(Clone, Debug)]
+pub struct Capture {
+	name: String,
+	quantifier: Quantifier,
+	patterns: Vec<Pattern>,
+	description: Option<String>,
+	limit: Option<Limit>,
+}
+
+/// A pattern of a [Capture], made of filters.
+///
+/// Each method corresponds to the filter with the same name.
+#[derive(Clone, Debug, Default)]
+pub struct Pattern {
+	filters: Vec<(&'static str, Vec<String>)>,
+}
+
+impl Command {
+	/// Returns a [Builder] for a command.
+	pub fn builder() -> Builder {
+		Builder::default()
+	}
+}
+
+impl Builder {
+	/// Sets the description of the command, like a `//!` doc comment.
+	pub fn description(mut self, s: impl Into<String>) -> Self {
+		self.description = Some(s.into());
+		self
+	}
+
+	/// Appends literal text. The text is matched as is, spaces included.
+	pub fn text(mut self, s: impl Into<String>) -> Self {
+		self.segments.push(Segment::Text(s.into()));
+		self
+	}
+
+	/// Appends a capture.
+	pub fn capture(mut self, c: Capture) -> Self {
+		self.segments.push(Segment::Capture(c));
+		self
+	}
+
+	/// Appends a group of captures that can match in any order, like `{}`.
+	pub fn group(mut self, cs: impl IntoIterator<Item = Capture>) -> Self {
+		self.segments.push(Segment::Group(cs.into_iter().collect()));
+		self
+	}
+
+	/// Appends a priority group, like `[]`.
+	pub fn priority_group(mut self, cs: impl IntoIterator<Item = Capture>) -> Self {
+		self.segments
+			.push(Segment::PriorityGroup(cs.into_iter().collect()));
+		self
+	}
+
+	/// Compiles the command.
+	///
+	/// The returned errors never have a location.
+	pub fn build(&self) -> crate::Result<Command> {
+		let segments = self
+			.segments
+			.iter()
+			.map(|seg| Some(("", seg.to_parsed())))
+			.collect();
+		let mut cmd = lower(segments, self.description.clone(), Vec::new(), false, |_| None)
+			.map_err(|mut errors| errors.swap_remove(0))?;
+		check_names(cmd.captures())?;
+
+		let limits = self
+			.segments
+			.iter()
+			.flat_map(|seg| match seg {
+				Segment::Text(_) => &[],
+				Segment::Capture(c) => std::slice::from_ref(c),
+				Segment::Group(cs) | Segment::PriorityGroup(cs) => cs.as_slice(),
+			})
+			.map(|c| c.limit);
+		for (c, limit) in cmd.captures_mut().zip(limits) {
+			if let Some(limit) = limit {
+				c.limit = limit;
+			}
+		}
+		Ok(cmd)
+	}
+}
+
+impl Segment {
+	fn to_parsed(&self) -> parser::Segment<'_> {
+		fn caps(cs: &[Capture]) -> Vec<parser::Capture<'_>> {
+			cs.iter().map(Capture::to_parsed).collect()
+		}
+		match self {
+			Self::Text(s) => parser::Segment::Text(s.clone()),
+			Self::Capture(c) => parser::Segment::Capture(c.to_parsed()),
+			Self::Group(cs) => parser::Segment::Group(caps(cs)),
+			Self::PriorityGroup(cs) => parser::Segment::PriorityGroup(caps(cs)),
+		}
+	}
+}
+
+impl Capture {
+	/// Creates a capture that matches a single word.
+	pub fn new(name: impl Into<String>) -> Self {
+		Self {
+			name: name.into(),
+			quantifier: Quantifier::Once,
+			patterns: Vec::new(),
+			description: None,
+			limit: None,
+		}
+	}
+
+	/// Sets the description of the capture, like a `///` doc comment.
+	pub fn description(mut self, s: impl Into<String>) -> Self {
+		self.description = Some(s.into());
+		self
+	}
+
+	/// Sets the quantifier; the default is [Quantifier::Once].
+	pub fn quantifier(mut self, q: Quantifier) -> Self {
+		self.quantifier = q;
+		self
+	}
+
+	/// Sets how many values the capture can match; the default is
+	/// [Limit::default].
+	pub fn limit(mut self, limit: Limit) -> Self {
+		self.limit = Some(limit);
+		self
+	}
+
+	/// Adds a pattern. The capture matches if any of its patterns matches.
+	pub fn pattern(mut self, p: Pattern) -> Self {
+		self.patterns.push(p);
+		self
+	}
+
+	fn to_parsed(&self) -> parser::Capture<'_> {
+		parser::Capture {
+			name: &self.name,
+			quantifier: self.quantifier,
+			patterns: self
+				.patterns
+				.iter()
+				.map(|p| parser::Pattern {
+					filters: p
+						.filters
+						.iter()
+						.map(|(name, args)| parser::Filter {
+							name,
+							args: args.clone(),
+							regex_args: false,
+							src: "",
+						})
+						.collect(),
+					src: "",
+				})
+				.collect(),
+			description: self.description.clone(),
+		}
+	}
+}
+
+impl Pattern {
+	/// Creates a pattern without any filters. At least one filter must be
+	/// added before the pattern is used in [Builder::build].
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	fn filter<I, S>(mut self, name: &'static str, args: I) -> Self
+	where
+		I: IntoIterator<Item = S>,
+		S: Into<String>,
+	{
+		self.filters
+			.push((name, args.into_iter().map(Into::into).collect()));
+		self
+	}
+
+	/// Adds an `eq` filter, matching any of the strings exactly.
+	pub fn eq<I: IntoIterator<Item = S>, S: Into<String>>(self, any_of: I) -> Self {
+		self.filter("eq", any_of)
+	}
+
+	/// Adds a `starts` filter, matching text that starts with any of the
+	/// strings. The strings are not part of the value.
+	pub fn starts<I: IntoIterator<Item = S>, S: Into<String>>(self, any_of: I) -> Self {
+		self.filter("starts", any_of)
+	}
+
+	/// Adds an `ends` filter, matching text that ends with any of the strings.
+	/// The strings are not part of the value.
+	pub fn ends<I: IntoIterator<Item = S>, S: Into<String>>(self, any_of: I) -> Self {
+		self.filter("ends", any_of)
+	}
+
+	/// Adds a `regex` filter. The regex is compiled by [Builder::build].
+	pub fn regex(self, re: impl Into<String>) -> Self {
+		self.filter("regex", [re])
+	}
+
+	/// Adds a `nocase` filter, making `eq`, `starts`, `ends` and `codeblock`
+	/// case insensitive.
+	pub fn nocase(self) -> Self {
+		self.filter("nocase", [""; 0])
+	}
+
+	/// Adds a `notrim` filter, keeping the delimiters of `starts` and `ends`
+	/// in the value.
+	pub fn notrim(self) -> Self {
+		self.filter("notrim", [""; 0])
+	}
+
+	/// Adds a `balanced` filter, skipping nested pairs of `starts` and `ends`
+	/// and delimiters inside any of the `quotes`. Requires both `starts` and
+	/// `ends`.
+	pub fn balanced<I: IntoIterator<Item = S>, S: Into<String>>(self, quotes: I) -> Self {
+		self.filter("balanced", quotes)
+	}
+
+	/// Adds an `escape` filter; an `ends` delimiter preceded by any of the
+	/// strings does not end the value. Requires `ends`.
+	pub fn escape<I: IntoIterator<Item = S>, S: Into<String>>(self, escapes: I) -> Self {
+		self.filter("escape", escapes)
+	}
+
+	/// Adds a `codeblock` filter, matching a markdown code block or inline
+	/// code in any of the languages, or in any language if there are none.
+	pub fn codeblock<I: IntoIterator<Item = S>, S: Into<String>>(self, langs: I) -> Self {
+		self.filter("codeblock", langs)
+	}
+
+	/// Adds an `extract` filter. The regex is compiled by [Builder::build].
+	pub fn extract(self, re: impl Into<String>) -> Self {
+		self.filter("extract", [re])
+	}
+}
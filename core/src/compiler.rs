@@ -0,0 +1,762 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2024 Taylan Gökkaya
+
+// This file is licensed under the terms of Apache-2.0 License.
+
+#[cfg(test)]
+mod tests;
+
+use std::{
+	collections::HashMap,
+	fmt,
+	mem,
+};
+
+use regex::Regex;
+
+use crate::{
+	ast::{
+		self,
+		Capture,
+		Pattern,
+		Segment,
+	},
+	parser::{
+		self,
+		Location,
+		Quantifier,
+	},
+	Diagnostic,
+	Error,
+	Limit,
+};
+
+/// A compiled command that can be used to match text.
+#[derive(Clone, Debug)]
+pub struct Command {
+	pub(crate) segments: Vec<Segment>,
+	/// The source, if compiled from source. It is parsed again to locate
+	/// segments and to lint the source, which are rarely needed.
+	pub(crate) source: Option<String>,
+	/// The text of the `//!` doc comments.
+	pub(crate) description: Option<String>,
+}
+
+impl Command {
+	/// Compiles a command.
+	pub fn new(s: &str) -> crate::Result<Self> {
+		compile(s, false).map_err(|mut errors| errors.swap_remove(0))
+	}
+
+	/// Compiles a command like [Command::new], but instead of stopping at the
+	/// first error, returns every error found in the source.
+	/// #### Examples
+	/// ```rust
+	/// let errors = malachi::Command::compile("?foo <a: stars('x')> <b: eq()>").unwrap_err();
+	///
+	/// assert_eq!(errors.len(), 2);
+	/// assert_eq!(errors[0].location.as_ref().map(|l| l.col), Some(10));
+	/// assert_eq!(errors[1].location.as_ref().map(|l| l.col), Some(26));
+	/// ```
+	pub fn compile(s: &str) -> Result<Self, Vec<Diagnostic>> {
+		compile(s, true).map_err(|errors| errors.into_iter().map(Diagnostic::from).collect())
+	}
+
+	/// Returns the description of the command, from its `//!` doc comments.
+	/// #### Examples
+	/// ```rust
+	/// let cmd = malachi::Command::new(
+	/// 	"//! Bans a user.
+	/// 	?ban
+	/// 	/// The user to ban.
+	/// 	<user>",
+	/// )?;
+	///
+	/// assert_eq!(Some("Bans a user."), cmd.description());
+	/// let user = cmd.segments().nth(1).unwrap();
+	/// assert_eq!(
+	/// 	Some("The user to ban."),
+	/// 	user.captures()[0].description.as_deref()
+	/// );
+	/// # Ok::<(), malachi::Error>(())
+	/// ```
+	pub fn description(&self) -> Option<&str> {
+		self.description.as_deref()
+	}
+
+	/// Returns an iterator over the [Segment]s that make up `self`.
+	pub fn segments(&self) -> std::slice::Iter<'_, Segment> {
+		self.segments.iter()
+	}
+
+	/// Returns an iterator over every [Capture] in `self`, including the ones
+	/// in groups, in the order they appear.
+	pub fn captures(&self) -> impl Iterator<Item = &Capture> + '_ {
+		self.segments.iter().flat_map(|seg| seg.captures())
+	}
+
+	/// Returns the [Capture] with the name `name`, if any.
+	pub fn capture(&self, name: &str) -> Option<&Capture> {
+		self.captures().find(|c| c.name == name)
+	}
+
+	/// Returns an iterator over the captures that must match for `self` to
+	/// match.
+	pub fn required_captures(&self) -> impl Iterator<Item = &Capture> + '_ {
+		self.captures().filter(|c| c.is_required())
+	}
+
+	/// Returns the values the capture `name` can match, if it only uses the
+	/// `eq` filter.
+	///
+	/// See [Capture::choices].
+	pub fn choices(&self, name: &str) -> Option<Vec<&str>> {
+		self.capture(name).and_then(Capture::choices)
+	}
+
+	/// Returns the literal text segments before the first capture or group.
+	/// #### Examples
+	/// ```rust
+	/// let cmd = malachi::Command::new("?tag add <name> <mode: eq('a', 'b')> <args*>")?;
+	///
+	/// assert_eq!(vec!["?tag", "add"], cmd.literal_prefix());
+	/// assert_eq!(Some(vec!["a", "b"]), cmd.choices("mode"));
+	/// let required: Vec<_> = cmd.required_captures().map(|c| c.name.as_str()).collect();
+	/// assert_eq!(vec!["name", "mode"], required);
+	/// # Ok::<(), malachi::Error>(())
+	/// ```
+	pub fn literal_prefix(&self) -> Vec<&str> {
+		self.segments
+			.iter()
+			.map_while(|seg| match seg {
+				Segment::Text(s) => Some(s.as_str()),
+				_ => None,
+			})
+			.collect()
+	}
+}
+
+impl fmt::Display for Command {
+	/// Writes the source of `self`, which compiles back into an equivalent
+	/// command. The alternate form (`{:#}`) puts every segment on its own line.
+	///
+	/// Capture limits are not part of the syntax so they are not written; a
+	/// command compiled from the output has the default limits unless it is
+	/// compiled with the same [CompileOptions][crate::CompileOptions].
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		if let Some(s) = &self.description {
+			ast::write_docs(f, "//!", s)?;
+		}
+		let sep = if f.alternate() { "\n" } else { " " };
+		for (i, seg) in self.segments.iter().enumerate() {
+			if i > 0 {
+				f.write_str(sep)?;
+			}
+			fmt::Display::fmt(seg, f)?;
+		}
+		Ok(())
+	}
+}
+
+/// Compiles a command; if `recover` is `true`, returns all the errors instead
+/// of only the first one.
+fn compile(s: &str, recover: bool) -> Result<Command, Vec<Error>> {
+	let (cmd, description, syntax_errors) = if recover {
+		parser::parse_command_recovering(s)
+	} else {
+		match parser::parse_command(s) {
+			Ok((cmd, description)) => (cmd.into_iter().map(Some).collect(), description, Vec::new()),
+			Err(e) => return Err(vec![Error::Syntax(e)]),
+		}
+	};
+	let errors = syntax_errors.into_iter().map(Error::Syntax).collect();
+	let mut cmd = lower(cmd, description, errors, recover, |src| {
+		Some(Location::of(s, src))
+	})?;
+	cmd.source = Some(s.to_owned());
+	Ok(cmd)
+}
+
+/// Transforms parsed segments into a [Command] and validates it.
+///
+/// `errors` are the errors found so far and `locate` returns the location of a
+/// part of the source.
+pub(crate) fn lower<'a>(
+	cmd: parser::Segments<'a>,
+	description: Option<String>,
+	mut errors: Vec<Error>,
+	recover: bool,
+	locate: impl Fn(&'a str) -> Option<Location>,
+) -> Result<Command, Vec<Error>> {
+	let mut locations = Vec::with_capacity(cmd.len());
+	let mut segments = Vec::with_capacity(cmd.len());
+	for seg in cmd {
+		let Some((src, seg)) = seg else {
+			locations.push(None);
+			segments.push(None);
+			continue;
+		};
+		locations.push(locate(src));
+		match Segment::try_from(seg) {
+			Ok(seg) => segments.push(Some(seg)),
+			Err(es) => {
+				errors.extend(
+					es.into_iter()
+						.map(|(error, src)| Error::Filter(error, locate(src))),
+				);
+				if !recover {
+					return Err(errors);
+				}
+				segments.push(None);
+			}
+		}
+	}
+
+	errors.extend(
+		validate(&segments)
+			.into_iter()
+			.map(|(error, i)| Error::Rule(error, locations[i].clone())),
+	);
+
+	// Report errors in the order they appear in the source.
+	errors.sort_by_key(|e| e.location().map_or(usize::MAX, |l| l.span.start));
+
+	if errors.is_empty() {
+		Ok(Command {
+			segments: segments.into_iter().flatten().collect(),
+			source: None,
+			description,
+		})
+	} else {
+		Err(errors)
+	}
+}
+
+impl Command {
+	/// Creates a command from segments that were not compiled from source,
+	/// checking the same rules as [Command::new].
+	#[cfg_attr(not(feature = "serde"), allow(dead_code))]
+	pub(crate) fn from_segments(
+		segments: Vec<Segment>,
+		description: Option<String>,
+	) -> crate::Result<Self> {
+		for p in segments.iter().flat_map(|s| s.captures()).flat_map(|c| &c.patterns) {
+			p.check()?;
+		}
+		check_names(segments.iter().flat_map(|s| s.captures()))?;
+		let segments = segments.into_iter().map(Some).collect::<Vec<_>>();
+		if let Some((error, _)) = validate(&segments).into_iter().next() {
+			return Err(error.into());
+		}
+
+		Ok(Self {
+			segments: segments.into_iter().flatten().collect(),
+			source: None,
+			description,
+		})
+	}
+
+	/// Parses the source of `self` again, returning it along with the source
+	/// text of each segment, or `None` if `self` was not compiled from source.
+	pub(crate) fn parse_source(&self) -> Option<(&str, Vec<(&str, parser::Segment<'_>)>)> {
+		let s = self.source.as_deref()?;
+		// The source compiled, so it parses.
+		let (segments, _) = parser::parse_command(s).ok()?;
+		Some((s, segments))
+	}
+
+	/// Returns where each segment is in the source, if compiled from source.
+	pub(crate) fn locations(&self) -> Vec<Option<Location>> {
+		match self.parse_source() {
+			Some((s, segments)) => segments
+				.into_iter()
+				.map(|(src, _)| Some(Location::of(s, src)))
+				.collect(),
+			None => vec![None; self.segments.len()],
+		}
+	}
+}
+
+/// Validates a sequence of segments, skipping the ones that failed to compile.
+/// Every error is returned along with the index of the offending segment.
+fn validate(segments: &[Option<Segment>]) -> Vec<(RuleError, usize)> {
+	let mut errors = Vec::new();
+	let mut names = HashMap::new();
+	for (i, seg) in segments.iter().enumerate() {
+		let Some(seg) = seg else { continue };
+		for c in seg.captures() {
+			if let Some(&first) = names.get(c.name.as_str()) {
+				let e = RuleError::DuplicateCapture {
+					name: c.name.clone(),
+					first,
+					second: i,
+				};
+				errors.push((e, i));
+			} else {
+				names.insert(c.name.as_str(), i);
+			}
+		}
+	}
+
+	for (i, w) in segments.windows(2).enumerate() {
+		if let [Some(left), Some(right)] = w {
+			if !left.is_deterministic() && !right.is_deterministic() {
+				errors.push((RuleError::NonDeterministicSequence, i + 1));
+			}
+		}
+	}
+
+	errors
+}
+
+/// Checks that every capture name can be written in the source of a command,
+/// which the parser already guarantees for commands compiled from source.
+pub(crate) fn check_names<'a>(
+	mut captures: impl Iterator<Item = &'a Capture>,
+) -> Result<(), RuleError> {
+	let valid = |name: &str| {
+		name.chars()
+			.all(|c| c.is_alphanumeric() || c == '-' || c == '_')
+	};
+	match captures.find(|c| !valid(&c.name)) {
+		Some(c) => Err(RuleError::InvalidName(c.name.clone())),
+		None => Ok(()),
+	}
+}
+
+/// Converts every item, returning all the errors if there are any.
+fn convert_all<T, U, E>(
+	xs: Vec<T>,
+	mut f: impl FnMut(T) -> Result<U, Vec<E>>,
+) -> Result<Vec<U>, Vec<E>> {
+	let mut vals = Vec::with_capacity(xs.len());
+	let mut errors = Vec::new();
+	for x in xs {
+		match f(x) {
+			Ok(x) => vals.push(x),
+			Err(es) => errors.extend(es),
+		}
+	}
+
+	if errors.is_empty() {
+		Ok(vals)
+	} else {
+		Err(errors)
+	}
+}
+
+impl Segment {
+	/// Returns the captures in this segment.
+	pub fn captures(&self) -> &[Capture] {
+		match self {
+			Self::Text(_) => &[],
+			Self::Capture(c) => std::slice::from_ref(c),
+			Self::Group(cs) | Self::PriorityGroup(cs) => cs,
+		}
+	}
+
+	fn is_deterministic(&self) -> bool {
+		match self {
+			Self::Text(_) => true,
+			Self::Capture(c) => c.is_deterministic(),
+			Self::Group(cs) | Self::PriorityGroup(cs) => cs.iter().all(|c| c.is_deterministic()),
+		}
+	}
+}
+
+impl Capture {
+	fn is_deterministic(&self) -> bool {
+		match self.quantifier {
+			Quantifier::Once => true,
+			_ if self.patterns.is_empty() => false,
+			_ => self.patterns.iter().all(|p| p.is_deterministic()),
+		}
+	}
+}
+
+impl Pattern {
+	/// Checks what [Pattern::try_from] would have rejected, for patterns that
+	/// were not compiled from source.
+	#[cfg_attr(not(feature = "serde"), allow(dead_code))]
+	fn check(&self) -> Result<(), FilterError> {
+		match self {
+			Self::Eq { any_of, .. } if any_of.is_empty() => {
+				Err(FilterError::MissingArgs(String::from("eq")))
+			}
+			Self::Extract { reg } if reg.is_empty() => {
+				Err(FilterError::MissingArgs(String::from("extract")))
+			}
+			Self::Delimited { starts, ends, .. } if starts.is_empty() && ends.is_empty() => {
+				Err(FilterError::MissingArgs(String::from("starts")))
+			}
+			Self::Delimited {
+				starts,
+				ends,
+				balanced: true,
+				..
+			} if starts.is_empty() || ends.is_empty() => Err(FilterError::Balanced),
+			Self::Delimited { ends, escapes, .. } if !escapes.is_empty() && ends.is_empty() => {
+				Err(FilterError::Escape)
+			}
+			_ => Ok(()),
+		}
+	}
+}
+
+impl<'a> TryFrom<parser::Segment<'a>> for Segment {
+	type Error = Vec<(FilterError, &'a str)>;
+
+	fn try_from(seg: parser::Segment<'a>) -> Result<Self, Self::Error> {
+		type Seg<'a> = parser::Segment<'a>;
+		match seg {
+			Seg::Text(s) => Ok(Self::Text(s)),
+			Seg::Capture(c) => Capture::try_from(c).map(Self::Capture),
+			Seg::Group(cs) => convert_all(cs, Capture::try_from).map(Self::Group),
+			Seg::PriorityGroup(cs) => convert_all(cs, Capture::try_from).map(Self::PriorityGroup),
+		}
+	}
+}
+
+impl<'a> TryFrom<parser::Capture<'a>> for Capture {
+	type Error = Vec<(FilterError, &'a str)>;
+
+	fn try_from(mut c: parser::Capture<'a>) -> Result<Self, Self::Error> {
+		convert_all(mem::take(&mut c.patterns), |p| {
+			Pattern::try_from(p).map_err(|e| vec![e])
+		})
+		.map(|patterns| Self {
+			name: c.name.to_string(),
+			quantifier: c.quantifier,
+			patterns,
+			description: c.description,
+			limit: Limit::default(),
+		})
+	}
+}
+
+impl<'a> TryFrom<parser::Pattern<'a>> for Pattern {
+	type Error = (FilterError, &'a str);
+
+	fn try_from(p: parser::Pattern<'a>) -> Result<Self, Self::Error> {
+		let parser::Pattern {
+			filters: mut v,
+			src,
+		} = p;
+		let mut no_case = false;
+		let mut no_trim = false;
+		let mut balanced = false;
+		let mut quotes = Vec::new();
+		// The parser never returns patterns without filters but the builder can.
+		if v.is_empty() {
+			return Err((FilterError::NoFilters, src));
+		}
+		if let Some(f) = v
+			.iter()
+			.find(|f| f.regex_args && !matches!(f.name, "extract" | "regex"))
+		{
+			return Err((FilterError::RegexArgs(f.name.to_string()), f.src));
+		}
+		v.retain(|f| {
+			if f.name == "nocase" {
+				no_case = true;
+				false
+			} else if f.name == "notrim" {
+				no_trim = true;
+				false
+			} else if f.name == "balanced" {
+				balanced = true;
+				quotes.extend(f.args.iter().filter(|s| !s.is_empty()).cloned());
+				false
+			} else {
+				true
+			}
+		});
+		let mut regs = Vec::new();
+		let mut escapes = Vec::new();
+		let mut filters = Vec::with_capacity(v.len());
+		for f in v {
+			if f.name == "regex" {
+				if f.args.is_empty() {
+					return Err((FilterError::MissingArgs("regex".into()), f.src));
+				}
+				regs.extend(f.args.into_iter().map(|s| (s, f.src)));
+			} else if f.name == "escape" {
+				if f.args.is_empty() {
+					return Err((FilterError::MissingArgs("escape".into()), f.src));
+				}
+				escapes.extend(f.args);
+			} else {
+				filters.push(f);
+			}
+		}
+
+		let reg = regs
+			.into_iter()
+			.map(|(s, src)| Regex::new(&s).map_err(|e| (FilterError::Regex(e), src)))
+			.collect::<Result<Vec<_>, _>>()?;
+		if filters.is_empty() {
+			if balanced {
+				return Err((FilterError::Balanced, src));
+			} else if !escapes.is_empty() {
+				return Err((FilterError::Escape, src));
+			}
+			return Ok(Self::Word { reg });
+		}
+
+		match filters[0].name {
+			"eq" => {
+				if no_trim || balanced || !escapes.is_empty() || !reg.is_empty() {
+					return Err((FilterError::Eq, src));
+				}
+				let mut any_of = Vec::new();
+				for f in &filters {
+					match f.name {
+						"eq" if f.args.is_empty() => {
+							return Err((FilterError::MissingArgs(String::from("eq")), f.src))
+						}
+						"eq" => any_of.extend(f.args.iter().map(|s| s.to_owned())),
+						"starts" | "ends" | "codeblock" | "extract" => return Err((FilterError::Eq, f.src)),
+						unknown => return Err((FilterError::UnknownFilter(unknown.to_string()), f.src)),
+					};
+				}
+
+				Ok(Self::Eq { any_of, no_case })
+			}
+			"starts" | "ends" => {
+				let mut starts = Vec::new();
+				let mut ends = Vec::new();
+
+				for f in &filters {
+					match f.name {
+						"starts" | "ends" if f.args.is_empty() => {
+							return Err((FilterError::MissingArgs(f.name.to_string()), f.src))
+						}
+						"starts" => {
+							starts.extend(f.args.iter().map(|s| s.to_string()));
+						}
+						"ends" => {
+							ends.extend(f.args.iter().map(|s| s.to_string()));
+						}
+						"eq" => return Err((FilterError::Eq, f.src)),
+						"codeblock" => return Err((FilterError::CodeBlock, f.src)),
+						"extract" => return Err((FilterError::Extract, f.src)),
+						unknown => return Err((FilterError::UnknownFilter(unknown.to_string()), f.src)),
+					}
+				}
+				if balanced && (starts.is_empty() || ends.is_empty()) {
+					return Err((FilterError::Balanced, src));
+				} else if !escapes.is_empty() && ends.is_empty() {
+					return Err((FilterError::Escape, src));
+				}
+				Ok(Self::Delimited {
+					starts,
+					ends,
+					reg,
+					no_case,
+					no_trim,
+					balanced,
+					quotes,
+					escapes,
+				})
+			}
+			"codeblock" => {
+				if no_trim || balanced || !escapes.is_empty() {
+					return Err((FilterError::CodeBlock, src));
+				}
+				let mut langs = Vec::new();
+				for f in &filters {
+					match f.name {
+						"codeblock" => langs.extend(f.args.iter().map(|s| s.to_string())),
+						"eq" => return Err((FilterError::Eq, f.src)),
+						"starts" | "ends" | "extract" => return Err((FilterError::CodeBlock, f.src)),
+						unknown => return Err((FilterError::UnknownFilter(unknown.to_string()), f.src)),
+					}
+				}
+				Ok(Self::CodeBlock {
+					langs,
+					reg,
+					no_case,
+				})
+			}
+			"extract" => {
+				if no_case || no_trim || balanced || !escapes.is_empty() || !reg.is_empty() {
+					return Err((FilterError::Extract, src));
+				}
+				let mut reg = Vec::new();
+				for f in &filters {
+					match f.name {
+						"extract" if f.args.is_empty() => {
+							return Err((FilterError::MissingArgs(String::from("extract")), f.src))
+						}
+						"extract" => {
+							for s in &f.args {
+								let r = ast::anchor(s);
+								reg.push(r.map_err(|e| (FilterError::Regex(e), f.src))?);
+							}
+						}
+						_ => return Err((FilterError::Extract, f.src)),
+					}
+				}
+				Ok(Self::Extract { reg })
+			}
+			unknown => Err((
+				FilterError::UnknownFilter(unknown.to_string()),
+				filters[0].src,
+			)),
+		}
+	}
+}
+
+/// Names of all the filters.
+const FILTERS: &[&str] = &[
+	"eq",
+	"starts",
+	"ends",
+	"nocase",
+	"notrim",
+	"regex",
+	"balanced",
+	"escape",
+	"codeblock",
+	"extract",
+];
+
+#[derive(Clone, Debug)]
+pub enum FilterError {
+	// Used when an incompatible fitler is used alongside the `eq` filter.
+	Eq,
+	UnknownFilter(String),
+	MissingArgs(String),
+	Regex(regex::Error),
+	// Used when `balanced` is not used along both `starts` and `ends`.
+	Balanced,
+	// Used when `escape` is not used along `ends`.
+	Escape,
+	// Used when an incompatible filter is used alongside the `codeblock` filter.
+	CodeBlock,
+	// Used when any other filter is used alongside the `extract` filter.
+	Extract,
+	// Used when a filter other than `extract` is given a regex argument.
+	RegexArgs(String),
+	// Used when a pattern has no filters.
+	NoFilters,
+}
+
+impl FilterError {
+	/// Returns the name of a filter that is similar to an unknown filter, if
+	/// any.
+	pub fn suggestion(&self) -> Option<&'static str> {
+		let Self::UnknownFilter(name) = self else {
+			return None;
+		};
+		let name = name.to_lowercase();
+		FILTERS
+			.iter()
+			.map(|x| (edit_distance(&name, x), *x))
+			.filter(|&(d, x)| d <= 2 && d < x.len())
+			.min_by_key(|&(d, _)| d)
+			.map(|(_, x)| x)
+	}
+}
+
+/// Levenshtein distance between `a` and `b`.
+fn edit_distance(a: &str, b: &str) -> usize {
+	let b: Vec<char> = b.chars().collect();
+	let mut prev: Vec<usize> = (0..=b.len()).collect();
+	for (i, ca) in a.chars().enumerate() {
+		let mut cur = vec![i + 1; b.len() + 1];
+		for (j, &cb) in b.iter().enumerate() {
+			let sub = prev[j] + usize::from(ca != cb);
+			cur[j + 1] = sub.min(prev[j + 1] + 1).min(cur[j] + 1);
+		}
+		prev = cur;
+	}
+	prev[b.len()]
+}
+
+impl std::error::Error for FilterError {}
+impl fmt::Display for FilterError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Regex(e) => <regex::Error as fmt::Display>::fmt(e, f),
+			Self::UnknownFilter(s) => {
+				write!(f, "unknown filter `{}`", s)?;
+				match self.suggestion() {
+					Some(x) => write!(f, "; did you mean `{}`?", x),
+					None => Ok(()),
+				}
+			}
+			Self::Eq => f.write_str("the `eq` filter can only be used along `nocase`"),
+			Self::MissingArgs(name) => write!(f, "`{}` takes at least 1 argument; 0 given", name),
+			Self::Balanced => {
+				f.write_str("the `balanced` filter requires both `starts` and `ends`")
+			}
+			Self::Escape => f.write_str("the `escape` filter requires `ends`"),
+			Self::CodeBlock => {
+				f.write_str("the `codeblock` filter can only be used along `nocase` and `regex`")
+			}
+			Self::Extract => f.write_str("the `extract` filter can not be used along other filters"),
+			Self::NoFilters => f.write_str("a pattern must have at least one filter"),
+			Self::RegexArgs(name) => write!(f, "the `{}` filter does not take regex arguments", name),
+		}
+	}
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum RuleError {
+	NonDeterministicSequence,
+	/// Used when more than one capture has the same name.
+	DuplicateCapture {
+		name: String,
+		/// The index of the segment the name first appears in.
+		first: usize,
+		/// The index of the segment the name appears in again.
+		second: usize,
+	},
+	/// Used when a command that was not compiled from source has a capture
+	/// name with characters other than letters, digits, `-` and `_`.
+	InvalidName(String),
+}
+
+impl From<regex::Error> for FilterError {
+	fn from(e: regex::Error) -> Self {
+		Self::Regex(e)
+	}
+}
+
+impl std::error::Error for RuleError {}
+impl fmt::Display for RuleError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::NonDeterministicSequence => {
+				f.write_str("command contains two non-deterministic captures next to each other")
+			}
+			Self::DuplicateCapture {
+				name,
+				first,
+				second,
+			} if first == second => write!(
+				f,
+				"the capture name `{}` is used more than once in segment {}",
+				name,
+				first + 1
+			),
+			Self::DuplicateCapture {
+				name,
+				first,
+				second,
+			} => write!(
+				f,
+				"the capture name `{}` is used in both segment {} and segment {}",
+				name,
+				first + 1,
+				second + 1
+			),
+			Self::InvalidName(name) => write!(
+				f,
+				"invalid capture name `{}`; names can only contain letters, digits, `-` and `_`",
+				name
+			),
+		}
+	}
+}
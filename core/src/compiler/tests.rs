@@ -0,0 +1,208 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2024 Taylan Gökkaya
+
+// This file is licensed under the terms of Apache-2.0 License.
+
+use super::{
+	Command,
+	FilterError,
+	RuleError,
+};
+use crate::{
+	Error,
+	Severity,
+};
+
+#[test]
+fn test_compile() {
+	let tests = &[
+		r".bet <amount>",
+		r".bible
+[
+	<book?: starts(`book=`)>
+	<chapter?: starts(`chapter=`); starts(`chap=`)>
+	<verse?: starts(`verse=`)>
+]",
+		r"no capture here!",
+		r"<maybe-prefix?> bar",
+		r"<dates*: extract(/\d+ \d+/)> <rest*>",
+		"?foo <*>",
+	];
+	for s in tests {
+		Command::new(s).unwrap();
+	}
+}
+
+#[test]
+fn test_filter_errors() {
+	let tests = &[
+		(r#"<x: balanced()>"#, "balanced"),
+		(r#"<x: starts("("), balanced()>"#, "balanced"),
+		(r#"<x: "foo", balanced()>"#, "eq"),
+		(r#"<x: escape("\\")>"#, "escape"),
+		(r#"<x: starts("-"), escape("\\")>"#, "escape"),
+		(r#"<x: "foo", escape("\\")>"#, "eq"),
+		(r#"<x: codeblock(), starts("-")>"#, "codeblock"),
+		(r#"<x: codeblock(), notrim()>"#, "codeblock"),
+		(r#"<x: "foo", codeblock()>"#, "eq"),
+		(r#"<x: extract(/a b/), starts("-")>"#, "extract"),
+		(r#"<x: extract(/a b/), nocase()>"#, "extract"),
+		(r#"<x: extract(/a b/), /a/>"#, "extract"),
+		(r#"<x: starts(/x+/)>"#, "regex args"),
+		(r#"<x: eq("a", /b/)>"#, "regex args"),
+		(r#"<x: starts("("), ends(")"), balanced(/"/)>"#, "regex args"),
+		(r"<x: extract(/x)|(y/)>", "regex"),
+	];
+	for (s, kind) in tests {
+		match (Command::new(s), *kind) {
+			(Err(Error::Filter(FilterError::Balanced, _)), "balanced") => (),
+			(Err(Error::Filter(FilterError::Escape, _)), "escape") => (),
+			(Err(Error::Filter(FilterError::CodeBlock, _)), "codeblock") => (),
+			(Err(Error::Filter(FilterError::Extract, _)), "extract") => (),
+			(Err(Error::Filter(FilterError::Eq, _)), "eq") => (),
+			(Err(Error::Filter(FilterError::RegexArgs(_), _)), "regex args") => (),
+			(Err(Error::Filter(FilterError::Regex(_), _)), "regex") => (),
+			(res, _) => panic!("{s}: expected a `{kind}` filter error, got {res:?}"),
+		}
+	}
+}
+
+#[test]
+fn test_duplicate_captures() {
+	let tests = &[
+		("<a> <a>", "a", 0, 1),
+		("?foo <a> {<b?> <a?>}", "a", 1, 2),
+		("[<x*> <y> <x?>]", "x", 0, 0),
+	];
+	for (s, name, first, second) in tests {
+		match Command::new(s) {
+			Err(Error::Rule(
+				RuleError::DuplicateCapture {
+					name: n,
+					first: f,
+					second: sec,
+				},
+				_,
+			)) => assert_eq!((*name, *first, *second), (n.as_str(), f, sec), "{s}"),
+			res => panic!("{s}: expected a duplicate capture error, got {res:?}"),
+		}
+	}
+}
+
+#[test]
+fn test_error_locations() {
+	let tests = &[
+		// Column is in characters, not bytes.
+		("ö <a b>", 1, 6, 6..7),
+		("?foo\n  <a: stars(`x`)>", 2, 7, 11..21),
+		("?foo <a: eq(`a`), notrim()>", 1, 10, 9..26),
+		("?foo <a*> <b*>", 1, 11, 10..14),
+		("?foo <a> <a>", 1, 10, 9..12),
+		("<a: /(/>", 1, 5, 4..7),
+	];
+	for (s, line_no, col, span) in tests {
+		let e = &Command::compile(s).unwrap_err()[0];
+		let loc = e.location.clone().unwrap_or_else(|| panic!("{s}: no location in {e:?}"));
+		assert_eq!(
+			(*line_no, *col, span.clone()),
+			(loc.line_no, loc.col, loc.span.clone()),
+			"{s}"
+		);
+		// Errors from `Command::new` have the same location.
+		let e = Command::new(s).unwrap_err();
+		assert_eq!(e.location(), Some(loc), "{s}");
+	}
+}
+
+#[test]
+fn test_syntax_errors() {
+	let e = match Command::new("?foo <a: starts(`x`) bar>") {
+		Err(Error::Syntax(e)) => e,
+		res => panic!("expected a syntax error, got {res:?}"),
+	};
+	assert_eq!(vec![">"], e.expected);
+	assert!(e.hint.is_some());
+
+	match Command::new("<a: stars(`x`)>") {
+		Err(Error::Filter(error, _)) => assert_eq!(Some("starts"), error.suggestion()),
+		res => panic!("expected a filter error, got {res:?}"),
+	}
+}
+
+#[test]
+fn test_compile_recovering() {
+	let tests: &[(&str, &[(usize, usize)])] = &[
+		("?foo <a: stars('x')> <b: eq()>", &[(1, 10), (1, 26)]),
+		("<a: eq('x'), nope()> <b: starts('x' bar>\n<c> <c>", &[
+			(1, 14),
+			(1, 37),
+			(2, 5),
+		]),
+		("<a*> {<b: nocase(), foo()> <c: bar()>} <d*>", &[(1, 21), (1, 32)]),
+		("?foo <a*> <b*>", &[(1, 11)]),
+		("?foo \\q <a: /(/>", &[(1, 7), (1, 13)]),
+	];
+
+	for (s, expected) in tests {
+		let errors = Command::compile(s).unwrap_err();
+		let got = errors
+			.iter()
+			.map(|d| {
+				let loc = d.location.as_ref().unwrap_or_else(|| panic!("{s}: no location in {d}"));
+				(loc.line_no, loc.col)
+			})
+			.collect::<Vec<_>>();
+		assert_eq!(*expected, got.as_slice(), "{s}: {errors:?}");
+		assert!(errors.iter().all(|d| d.severity == Severity::Error));
+	}
+
+	assert!(Command::compile("?foo <a*> <b: starts('x')>").is_ok());
+
+	// The errors are kept along with their messages.
+	let errors = Command::compile("?foo <a: stars('x')> <b*> <b>").unwrap_err();
+	assert!(matches!(
+		&errors[0].error,
+		Some(Error::Filter(FilterError::UnknownFilter(s), _)) if s == "stars",
+	));
+	assert!(matches!(
+		&errors[1].error,
+		Some(Error::Rule(RuleError::DuplicateCapture { name, .. }, _)) if name == "b",
+	));
+}
+
+#[test]
+fn test_display() {
+	let tests = &[
+		("?foo   <a>\n<b+>", "?foo <a> <b+>"),
+		(r"\<tag\ x\\ \{y", r"\<tag\ x\\ \{y"),
+		("<a: 'x', \"y\\\"\"; nocase(), eq(`z`)>", r#"<a: eq("x", "y\""); nocase(), eq("z")>"#),
+		(
+			r"<a?: notrim(), ends('`'), starts('`'), /x\/y/, balanced(), escape('\\')>",
+			r#"<a?: starts("`"), ends("`"), notrim(), balanced(), escape("\\"), /x\/y/>"#,
+		),
+		(r"{<a*: codeblock(), /^\w+$/> <b>}", r#"{<a*: codeblock(), /^\w+$/> <b>}"#),
+		(r"[<a: extract(/\d+ \d+/)>]", r"[<a: extract(/\d+ \d+/)>]"),
+		("<a: notrim()>", "<a>"),
+		("<a: nocase(); eq('x')>", "<a: notrim(); eq(\"x\")>"),
+		(r#"?x <a*: regex("")> <b*>"#, r#"?x <a*: regex("")> <b*>"#),
+		(r"\//a", r"\//a"),
+		(
+			"/// A.\n//! Cmd.\n<a> {\n/// B.\n///\n///  C.\n<b>}",
+			"//! Cmd.\n/// A.\n<a> {/// B.\n///\n///  C.\n<b>}",
+		),
+	];
+
+	for (s, expected) in tests {
+		let cmd = Command::new(s).unwrap_or_else(|e| panic!("{s}: {e}"));
+		let got = cmd.to_string();
+		assert_eq!(*expected, got, "{s}");
+		// The output compiles into the same command.
+		let again = Command::new(&got).unwrap_or_else(|e| panic!("{got}: {e}"));
+		assert_eq!(got, again.to_string());
+	}
+
+	let cmd = Command::new("?foo {<a> <b: eq('x'); starts('-')>}").unwrap();
+	let pretty = "?foo\n{\n\t<a>\n\t<b:\n\t\teq(\"x\");\n\t\tstarts(\"-\");\n\t>\n}";
+	assert_eq!(pretty, format!("{:#}", cmd));
+	assert_eq!(cmd.to_string(), Command::new(pretty).unwrap().to_string());
+}
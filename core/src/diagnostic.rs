@@ -0,0 +1,76 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2024 Taylan Gökkaya
+
+// This file is licensed under the terms of Apache-2.0 License.
+
+use std::fmt;
+
+use crate::{
+	errors::Location,
+	Error,
+};
+
+/// How serious a [Diagnostic] is.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+pub enum Severity {
+	/// The command can not be compiled.
+	Error,
+	/// The command compiles but probably does not do what was intended.
+	Warning,
+}
+
+/// A problem found in the source of a command.
+#[derive(Clone, Debug)]
+pub struct Diagnostic {
+	pub severity: Severity,
+	/// A description of the problem.
+	pub message: String,
+	/// Where the problem is in the source, if known.
+	pub location: Option<Location>,
+	/// A suggestion on how to fix the problem, if there is one.
+	pub hint: Option<String>,
+	/// The error this was made from, if it is an error.
+	pub error: Option<Error>,
+}
+
+impl From<Error> for Diagnostic {
+	fn from(e: Error) -> Self {
+		let location = e.location();
+		let (message, hint) = match &e {
+			Error::Syntax(e) => (e.message(), e.hint.map(String::from)),
+			Error::Filter(e, _) => (e.to_string(), None),
+			Error::Rule(e, _) => (e.to_string(), None),
+		};
+
+		Self {
+			severity: Severity::Error,
+			message,
+			location,
+			hint,
+			error: Some(e),
+		}
+	}
+}
+
+impl fmt::Display for Severity {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Error => f.write_str("error"),
+			Self::Warning => f.write_str("warning"),
+		}
+	}
+}
+
+impl fmt::Display for Diagnostic {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}: ", self.severity)?;
+		if let Some(loc) = &self.location {
+			write!(f, "{}: ", loc)?;
+		}
+		f.write_str(&self.message)?;
+		if let Some(hint) = &self.hint {
+			write!(f, " (help: {})", hint)?;
+		}
+		Ok(())
+	}
+}
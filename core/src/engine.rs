@@ -5,17 +5,32 @@
 
 mod capture;
 mod error;
+mod find;
 mod list;
 mod literal;
+mod mismatch;
+mod partial;
 mod pattern;
 
-use std::collections::HashMap;
-
 pub(crate) use error::IResult;
+pub use find::FindIter;
+pub(crate) use list::priority;
 use list::List;
+pub use mismatch::{
+	Expected,
+	Mismatch,
+};
+pub use partial::PartialMatch;
+
+use std::ops::Range;
+
+use nom::Offset;
 
 use crate::{
-	args::Match,
+	args::{
+		Groups,
+		Match,
+	},
 	ast::Segment,
 	compiler::Command,
 	Args,
@@ -26,15 +41,86 @@ macro_rules! err {
 }
 pub(crate) use err;
 
+/// A single value matched by a pattern.
+#[derive(Clone, Debug)]
+pub(crate) struct Value<'c, 't> {
+	pub text: &'t str,
+	pub groups: Groups<'c, 't>,
+	/// The escapes of the pattern that matched, for [Args::unescaped].
+	pub escapes: &'c [String],
+}
+
+impl<'t> From<&'t str> for Value<'_, 't> {
+	fn from(text: &'t str) -> Self {
+		Self {
+			text,
+			groups: Groups::default(),
+			escapes: &[],
+		}
+	}
+}
+
+/// Values matched by a capture, the counterpart of [Match].
+pub(crate) enum Values<'c, 't> {
+	Once(Value<'c, 't>),
+	Many(Vec<Value<'c, 't>>),
+}
+
+impl<'c, 't> Values<'c, 't> {
+	/// Splits the values into their texts, groups, byte ranges in `input` and
+	/// escapes.
+	fn split(self, input: &'t str) -> Split<'c, 't> {
+		let span = |text: &str| {
+			let start = input.offset(text);
+			start..start + text.len()
+		};
+
+		match self {
+			Self::Once(v) => Split {
+				m: Match::Once(v.text),
+				groups: vec![v.groups],
+				spans: vec![span(v.text)],
+				escapes: vec![v.escapes],
+			},
+			Self::Many(vs) => {
+				let mut vals = Vec::with_capacity(vs.len());
+				let mut groups = Vec::with_capacity(vs.len());
+				let mut spans = Vec::with_capacity(vs.len());
+				let mut escapes = Vec::with_capacity(vs.len());
+				for v in vs {
+					spans.push(span(v.text));
+					vals.push(v.text);
+					groups.push(v.groups);
+					escapes.push(v.escapes);
+				}
+				Split {
+					m: Match::Many(vals),
+					groups,
+					spans,
+					escapes,
+				}
+			}
+		}
+	}
+}
+
+/// The parts of [Values], as stored in [Args].
+pub(crate) struct Split<'c, 't> {
+	pub m: Match<'t>,
+	pub groups: Vec<Groups<'c, 't>>,
+	pub spans: Vec<Range<usize>>,
+	pub escapes: Vec<&'c [String]>,
+}
+
 enum MatchResult<'c, 't> {
-	Once(&'c str, Match<'t>),
-	Many(Vec<(&'c str, Match<'t>)>),
+	Once(&'c str, Values<'c, 't>),
+	Many(Vec<(&'c str, Values<'c, 't>)>),
 }
 
 impl Command {
 	/// Match this [Command] to the given text, returning captures if any.
 	pub fn get_matches<'c, 't>(&'c self, s: &'t str) -> Option<Args<'c, 't>> {
-		Segments(self.0.as_slice()).get_matches(s)
+		Segments(self.segments.as_slice()).get_matches(s)
 	}
 
 	/// Returns true if the command matches the string at least partially.
@@ -52,7 +138,7 @@ impl Command {
 	/// # Ok::<(), malachi::Error>(())
 	/// ```
 	pub fn has_prefix(&self, s: &str) -> bool {
-		!self.0.is_empty() && Segments(&self.0[..1]).get_matches(s).is_some()
+		!self.segments.is_empty() && Segments(&self.segments[..1]).get_matches(s).is_some()
 	}
 }
 
@@ -107,29 +193,43 @@ impl<'c, 't> Segments<'c> {
 			return None;
 		}
 
-		let mut vals = HashMap::new();
+		match self.match_prefix(input) {
+			(args, None) => Some(args),
+			_ => None,
+		}
+	}
+
+	/// Matches the segments in order until one fails.
+	///
+	/// Returns the captures of the segments that matched, with the input after
+	/// them as `rest`, and the index of the segment that failed, if any.
+	fn match_prefix(self, input: &'t str) -> (Args<'c, 't>, Option<usize>) {
+		let mut args = Args::default();
 		let mut remaining = input;
+		let mut failed = None;
 
 		for i in 0..self.0.len() {
 			let segs = Self(&self.0[i..]);
-			let (new_rem, val) = segs.get_match(remaining)?;
+			let Some((new_rem, val)) = segs.get_match(remaining) else {
+				failed = Some(i);
+				break;
+			};
 			remaining = new_rem;
 			match val {
 				Some(MatchResult::Once(key, val)) => {
-					vals.insert(key, val);
+					args.insert(key, val.split(input));
 				}
 				Some(MatchResult::Many(matches)) => {
 					for (key, val) in matches {
-						vals.insert(key, val);
+						args.insert(key, val.split(input));
 					}
 				}
 				_ => (),
 			};
 		}
 
-		Some(Args {
-			rest: remaining,
-			vals,
-		})
+		args.rest = remaining;
+		args.sort();
+		(args, failed)
 	}
 }
@@ -4,9 +4,14 @@
 // This file is licensed under the terms of Apache-2.0 License.
 
 use super::{
-	pattern::any_of,
+	error::Dummy,
+	pattern::{
+		any_of,
+		word,
+	},
 	IResult,
-	Match,
+	Value,
+	Values,
 };
 use crate::{
 	ast::Capture,
@@ -14,21 +19,23 @@ use crate::{
 		prelude::*,
 		Quantifier,
 	},
+	Limit,
+	Overflow,
 };
 
-fn try_match<'a, F, G>(
+fn try_match<'c, 'a, F, G>(
 	input: &'a str,
 	mut parser: F,
 	mut good: G,
-) -> IResult<&'a str, Option<Match<'a>>>
+) -> IResult<&'a str, Option<Values<'c, 'a>>>
 where
-	F: FnMut(&'a str) -> IResult<&'a str, &'a str>,
+	F: FnMut(&'a str) -> IResult<&'a str, Value<'c, 'a>>,
 	G: FnMut(&'a str) -> bool,
 {
 	// Try consuming and see if it still works.
 	if let Ok((remaining, val)) = (parser)(input) {
 		if good(remaining) {
-			Ok((remaining, Some(Match::Once(val))))
+			Ok((remaining, Some(Values::Once(val))))
 		} else {
 			Ok((input, None))
 		}
@@ -41,65 +48,61 @@ impl Capture {
 	/// Tries matching self.
 	// Patterns that may potentially match and those that can match multiple times
 	// are limited by the `good` function. `good() == false` will stop the match.
-	pub fn get_match<'a, F>(&self, input: &'a str, good: F) -> IResult<&'a str, Option<Match<'a>>>
+	pub(crate) fn get_match<'c, 'a, F>(
+		&'c self,
+		input: &'a str,
+		good: F,
+	) -> IResult<&'a str, Option<Values<'c, 'a>>>
 	where
 		F: FnMut(&'a str) -> bool,
 	{
 		match self.quantifier {
 			Quantifier::Once => {
 				if self.patterns.is_empty() {
-					let word = verify(
-						preceded(multispace0, take_till(|c: char| c.is_whitespace())),
-						|s: &str| !s.is_empty(),
-					);
-					map(word, |x| Some(Match::Once(x)))(input)
+					map(word, |x| Some(Values::Once(x)))(input)
 				} else {
 					map(preceded(multispace0, any_of(&self.patterns)), |x| {
-						Some(Match::Once(x))
+						Some(Values::Once(x))
 					})(input)
 				}
 			}
 			Quantifier::MaybeOnce => {
 				if self.patterns.is_empty() {
-					let word = verify(
-						preceded(multispace0, take_till(|c: char| c.is_whitespace())),
-						|s: &str| !s.is_empty(),
-					);
 					try_match(input, word, good)
 				} else {
 					try_match(input, any_of(&self.patterns), good)
 				}
 			}
 			Quantifier::Many1 => if self.patterns.is_empty() {
-				let parser = verify(
-					preceded(multispace0, take_till(|c: char| c.is_whitespace())),
-					|s: &str| !s.is_empty(),
-				);
-				try_many1(input, parser, good)
+				try_many1(input, word, good, self.limit)
 			} else {
 				let parser = preceded(multispace0, any_of(&self.patterns));
-				try_many1(input, parser, good)
+				try_many1(input, parser, good, self.limit)
 			}
-			.map(|(rem, vals)| (rem, Some(Match::Many(vals)))),
-			Quantifier::Many0 => if self.patterns.is_empty() {
-				let parser = verify(
-					preceded(multispace0, take_till(|c: char| c.is_whitespace())),
-					|s: &str| !s.is_empty(),
-				);
-				try_many1(input, parser, good)
+			.map(|(rem, vals)| (rem, Some(Values::Many(vals)))),
+			Quantifier::Many0 => match if self.patterns.is_empty() {
+				try_many1(input, word, good, self.limit)
 			} else {
 				let parser = preceded(multispace0, any_of(&self.patterns));
-				try_many1(input, parser, good)
-			}
-			.map(|(rem, vals)| (rem, Some(Match::Many(vals))))
-			.or(Ok((input, None))),
+				try_many1(input, parser, good, self.limit)
+			} {
+				Ok((rem, vals)) => Ok((rem, Some(Values::Many(vals)))),
+				// Exceeding the limit is a failure, not an absence of values.
+				Err(nom::Err::Error(_)) => Ok((input, None)),
+				Err(e) => Err(e),
+			},
 		}
 	}
 }
 
-fn try_many1<'a, F, G>(input: &'a str, mut inner: F, mut good: G) -> IResult<&'a str, Vec<&'a str>>
+fn try_many1<'c, 'a, F, G>(
+	input: &'a str,
+	mut inner: F,
+	mut good: G,
+	limit: Limit,
+) -> IResult<&'a str, Vec<Value<'c, 'a>>>
 where
-	F: FnMut(&'a str) -> IResult<&'a str, &'a str>,
+	F: FnMut(&'a str) -> IResult<&'a str, Value<'c, 'a>>,
 	G: FnMut(&'a str) -> bool,
 {
 	// If we match, keep matching until good returns false.
@@ -109,12 +112,15 @@ where
 	let mut last_good_rem = remaining;
 	let mut last_good_count = 1_usize;
 	while let Ok((new_rem, val)) = inner(remaining) {
-		if vals.len() > 50 {
+		if vals.len() >= limit.max() && limit.overflow() == Overflow::Truncate {
 			break;
 		}
 		vals.push(val);
 		remaining = new_rem;
 		if good(new_rem) {
+			if vals.len() > limit.max() {
+				return Err(nom::Err::Failure(Dummy));
+			}
 			last_good_rem = new_rem;
 			last_good_count = vals.len();
 		}
@@ -0,0 +1,104 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2024 Taylan Gökkaya
+
+// This file is licensed under the terms of Apache-2.0 License.
+
+use std::ops::Range;
+
+use super::Segments;
+use crate::{
+	Args,
+	Command,
+};
+
+impl Command {
+	/// Returns the first match of `self` starting at any word in `text`, with
+	/// its byte range in `text`.
+	///
+	/// Unlike [Command::get_matches], the match does not have to start at the
+	/// beginning of `text`. The spans in the returned [Args] are relative to
+	/// `text`.
+	///
+	/// Captures take whole words, so a capture is never found next to literal
+	/// text in the same word: `\[\[<name+>]]` finds nothing in
+	/// `look [[black lotus]] here` since `<name+>` would have to end inside
+	/// `lotus]]`. Put spaces around such captures or use a pattern with
+	/// delimiters, such as `<name: starts('[['), ends(']]')>`.
+	/// #### Examples
+	/// ```rust
+	/// let cmd = malachi::Command::new("<ticker: starts('$'), /^[A-Z]+$/>")?;
+	/// let text = "thoughts on $AAPL and $MSFT";
+	///
+	/// let (span, args) = cmd.find(text).unwrap();
+	/// assert_eq!(12..17, span);
+	/// assert_eq!(Some("AAPL"), args.get_once("ticker"));
+	///
+	/// let tickers: Vec<_> = cmd.find_iter(text).map(|(span, _)| &text[span]).collect();
+	/// assert_eq!(vec!["$AAPL", "$MSFT"], tickers);
+	/// # Ok::<(), malachi::Error>(())
+	/// ```
+	pub fn find<'c, 't>(&'c self, text: &'t str) -> Option<(Range<usize>, Args<'c, 't>)> {
+		self.find_iter(text).next()
+	}
+
+	/// Returns an iterator over the non-overlapping matches of `self` in
+	/// `text`, each starting at a word, with their byte ranges in `text`.
+	///
+	/// See [Command::find].
+	pub fn find_iter<'c, 't>(&'c self, text: &'t str) -> FindIter<'c, 't> {
+		FindIter {
+			segments: Segments(&self.segments),
+			text,
+			pos: 0,
+		}
+	}
+}
+
+/// An iterator over the matches of a [Command] in a text, returned by
+/// [Command::find_iter].
+#[derive(Clone)]
+pub struct FindIter<'c, 't> {
+	segments: Segments<'c>,
+	text: &'t str,
+	pos: usize,
+}
+
+impl<'c, 't> Iterator for FindIter<'c, 't> {
+	type Item = (Range<usize>, Args<'c, 't>);
+
+	fn next(&mut self) -> Option<Self::Item> {
+		while let Some(start) = self.next_word() {
+			let input = &self.text[start..];
+			// Words are not empty so this is the start of the next character.
+			let next = start + input.chars().next().map_or(1, char::len_utf8);
+			if let Some(mut args) = self.segments.get_matches(input) {
+				let end = self.text.len() - args.rest.len();
+				args.offset_spans(start);
+				// A match can be empty if every segment is optional.
+				self.pos = end.max(next);
+				return Some((start..end, args));
+			}
+			self.pos = next;
+		}
+		None
+	}
+}
+
+impl FindIter<'_, '_> {
+	/// Returns the start of the first word at or after `self.pos`.
+	fn next_word(&self) -> Option<usize> {
+		let mut prev_ws = self.pos == 0
+			|| self.text[..self.pos]
+				.chars()
+				.next_back()
+				.is_some_and(char::is_whitespace);
+
+		for (i, c) in self.text.get(self.pos..)?.char_indices() {
+			if !c.is_whitespace() && prev_ws {
+				return Some(self.pos + i);
+			}
+			prev_ws = c.is_whitespace();
+		}
+		None
+	}
+}
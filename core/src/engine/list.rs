@@ -10,22 +10,30 @@ use crate::{
 	},
 	engine::{
 		err,
-		pattern::any_of,
+		error::Dummy,
+		pattern::{
+			any_of,
+			word,
+		},
 		IResult,
-		Match,
+		Value,
+		Values,
 	},
 	parser::{
 		prelude::*,
 		Quantifier,
 	},
+	Limit,
+	Overflow,
 };
 
 #[derive(Clone)]
 struct MatchState<'c, 't> {
 	name: &'c str,
 	quantifier: Quantifier,
-	vals: RefCell<Vec<&'t str>>,
+	vals: RefCell<Vec<Value<'c, 't>>>,
 	patterns: &'c [Pattern],
+	limit: Limit,
 }
 
 impl<'c, 't> MatchState<'c, 't> {
@@ -35,11 +43,20 @@ impl<'c, 't> MatchState<'c, 't> {
 	}
 
 	fn is_done(&self) -> bool {
-		!self.vals.borrow().is_empty()
-			&& matches!(self.quantifier, Quantifier::Once | Quantifier::MaybeOnce)
+		let n = self.vals.borrow().len();
+		match self.quantifier {
+			Quantifier::Once | Quantifier::MaybeOnce => n > 0,
+			Quantifier::Many0 | Quantifier::Many1 => {
+				n >= self.limit.max() && self.limit.overflow() == Overflow::Truncate
+			}
+		}
 	}
 
-	fn finalize(self) -> Option<(&'c str, Match<'t>)> {
+	fn is_over_limit(&self) -> bool {
+		self.vals.borrow().len() > self.limit.max()
+	}
+
+	fn finalize(self) -> Option<(&'c str, Values<'c, 't>)> {
 		if self.vals.borrow().is_empty() {
 			return None;
 		}
@@ -51,23 +68,32 @@ impl<'c, 't> MatchState<'c, 't> {
 				.into_inner()
 				.into_iter()
 				.next()
-				.map(|x| (name, Match::Once(x))),
-			Q::Many0 | Q::Many1 => Some((name, Match::Many(self.vals.into_inner()))),
+				.map(|x| (name, Values::Once(x))),
+			Q::Many0 | Q::Many1 => Some((name, Values::Many(self.vals.into_inner()))),
 		}
 	}
 
-	fn get_match(&self, input: &'t str) -> IResult<&'t str, &'t str> {
+	fn get_match(&self, input: &'t str) -> IResult<&'t str, Value<'c, 't>> {
 		if self.patterns.is_empty() {
-			verify(
-				preceded(multispace0, take_till(|c: char| c.is_whitespace())),
-				|s: &str| !s.is_empty(),
-			)(input)
+			word(input)
 		} else {
 			preceded(multispace0, any_of(self.patterns))(input)
 		}
 	}
 }
 
+/// The order captures in a group are tried in; lower is tried first.
+pub(crate) fn priority(quantifier: Quantifier, has_pattern: bool) -> u8 {
+	type Q = Quantifier;
+	match quantifier {
+		Q::Once | Q::Many1 if has_pattern => 0,
+		Q::Once | Q::Many1 => 1,
+		Q::MaybeOnce if has_pattern => 2,
+		Q::Many0 if has_pattern => 3,
+		Q::MaybeOnce | Q::Many0 => 4,
+	}
+}
+
 #[derive(Clone)]
 pub struct List<'c, 't>(Vec<MatchState<'c, 't>>);
 
@@ -88,29 +114,37 @@ impl<'c, 't> List<'c, 't> {
 				quantifier: cap.quantifier,
 				vals: RefCell::new(Vec::new()),
 				patterns: cap.patterns.as_slice(),
+				limit: cap.limit,
 			})
 			.collect();
 
 		if !keep_order {
-			states.sort_by(|a, b| {
-				type Q = Quantifier;
-				fn priority(s: &MatchState) -> u8 {
-					let has_pattern = !s.patterns.is_empty();
-					match s.quantifier {
-						Q::Once | Q::Many1 if has_pattern => 0,
-						Q::Once | Q::Many1 => 1,
-						Q::MaybeOnce if has_pattern => 2,
-						Q::Many0 if has_pattern => 3,
-						Q::MaybeOnce | Q::Many0 => 4,
-					}
-				}
-
-				priority(a).cmp(&priority(b))
-			});
+			states.sort_by_key(|s| priority(s.quantifier, !s.patterns.is_empty()));
 		}
 		Self(states)
 	}
 
+	/// Matches as many values as possible without looking ahead.
+	///
+	/// Returns the remaining input and the name of the first capture that
+	/// still needs a value, if any.
+	pub fn furthest(&self, input: &'t str) -> (&'t str, Option<&'c str>) {
+		let mut remaining = input;
+		'outer: loop {
+			for state in self.0.iter().filter(|x| !x.is_done()) {
+				if let Ok((new_rem, val)) = state.get_match(remaining) {
+					remaining = new_rem;
+					state.vals.borrow_mut().push(val);
+					continue 'outer;
+				}
+			}
+			break;
+		}
+
+		let missing = self.0.iter().find(|x| x.needs_more()).map(|x| x.name);
+		(remaining, missing)
+	}
+
 	fn is_acceptable(&self) -> bool {
 		self.0.iter().all(|x| !x.needs_more())
 	}
@@ -119,7 +153,7 @@ impl<'c, 't> List<'c, 't> {
 		&self,
 		input: &'t str,
 		mut good: F,
-	) -> IResult<&'t str, Vec<(&'c str, Match<'t>)>>
+	) -> IResult<&'t str, Vec<(&'c str, Values<'c, 't>)>>
 	where
 		F: FnMut(&'t str) -> bool,
 	{
@@ -135,6 +169,10 @@ impl<'c, 't> List<'c, 't> {
 					state.vals.borrow_mut().push(val);
 					has_matched = true;
 					if self.is_acceptable() && good(new_rem) {
+						// Only captures that fail on overflow can exceed their limits.
+						if self.0.iter().any(|x| x.is_over_limit()) {
+							return Err(nom::Err::Failure(Dummy));
+						}
 						last_good_rem = new_rem;
 						last_good_state.clone_from(self);
 					}
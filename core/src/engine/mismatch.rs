@@ -0,0 +1,190 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2024 Taylan Gökkaya
+
+// This file is licensed under the terms of Apache-2.0 License.
+
+use std::{
+	collections::HashMap,
+	fmt,
+};
+
+use nom::Offset;
+
+use super::{
+	literal,
+	List,
+	Segments,
+};
+use crate::{
+	ast::{
+		Capture,
+		Segment,
+	},
+	Command,
+};
+
+/// Why a [Command] did not match a text, returned by [Command::diagnose].
+///
+/// Lifetime `'c` refers to the command and `'t` refers to the text that was
+/// matched.
+#[derive(Clone, Debug)]
+pub struct Mismatch<'c, 't> {
+	/// The index of the segment that failed to match.
+	pub segment: usize,
+	/// What the segment expected.
+	pub expected: Expected<'c>,
+	/// The byte offset in the text where matching stopped, after any
+	/// whitespace.
+	pub position: usize,
+	/// The whitespace delimited word at `position`, or `None` if the text
+	/// ended.
+	pub found: Option<&'t str>,
+}
+
+/// What a [Command] expected where it stopped matching.
+#[derive(Copy, Clone, Debug)]
+pub enum Expected<'c> {
+	/// Literal text.
+	Text(&'c str),
+	/// A value for a capture; either a capture segment or one in a group.
+	Capture(&'c Capture),
+}
+
+impl<'c, 't> Mismatch<'c, 't> {
+	fn new(segment: usize, expected: Expected<'c>, input: &'t str, remaining: &'t str) -> Self {
+		let remaining = remaining.trim_start();
+		Self {
+			segment,
+			expected,
+			position: input.offset(remaining),
+			found: remaining.split_whitespace().next(),
+		}
+	}
+}
+
+impl Command {
+	/// Explains why `self` does not match `input`.
+	///
+	/// Returns `None` if `self` matches `input` or has no segments.
+	/// #### Examples
+	/// ```rust
+	/// use malachi::Expected;
+	///
+	/// let cmd = malachi::Command::new("?give <user> <amount: /^\\d+$/>")?;
+	/// let mismatch = cmd.diagnose("?give bob lots").unwrap();
+	///
+	/// assert!(matches!(mismatch.expected, Expected::Capture(c) if c.name == "amount"));
+	/// assert_eq!(Some("lots"), mismatch.found);
+	/// assert_eq!(10, mismatch.position);
+	/// assert_eq!("expected <amount> at `lots`", mismatch.to_string());
+	/// # Ok::<(), malachi::Error>(())
+	/// ```
+	pub fn diagnose<'c, 't>(&'c self, input: &'t str) -> Option<Mismatch<'c, 't>> {
+		let segs = &self.segments;
+		let (args, failed) = Segments(segs).match_prefix(input);
+		let i = failed?;
+
+		let mut seen = HashMap::new();
+		furthest_mismatch(segs, 0, input, input, &mut seen).or_else(|| {
+			// Every segment can match on its own, so the match failed because
+			// of a limit.
+			let expected = match &segs[i] {
+				Segment::Text(s) => Expected::Text(s),
+				Segment::Capture(c) => Expected::Capture(c),
+				Segment::Group(caps) | Segment::PriorityGroup(caps) => {
+					Expected::Capture(caps.iter().find(|c| c.is_required()).or(caps.first())?)
+				}
+			};
+			Some(Mismatch::new(i, expected, input, args.rest))
+		})
+	}
+}
+
+/// Matches `segs[i..]` against `remaining`, a suffix of `input`, without
+/// looking ahead, and returns the mismatch that got the furthest into `input`.
+///
+/// Every way a capture can match on its own is tried, since an earlier end
+/// can make the following segments fail sooner. Returns `None` if the
+/// segments can match up to the end.
+///
+/// `seen` caches the results by segment index and offset into `input`.
+fn furthest_mismatch<'c, 't>(
+	segs: &'c [Segment],
+	i: usize,
+	input: &'t str,
+	remaining: &'t str,
+	seen: &mut HashMap<(usize, usize), Option<Mismatch<'c, 't>>>,
+) -> Option<Mismatch<'c, 't>> {
+	// Reaching the end means the segments matched.
+	let seg = segs.get(i)?;
+	let key = (i, input.offset(remaining));
+	if let Some(m) = seen.get(&key) {
+		return m.clone();
+	}
+
+	let ends = match seg {
+		Segment::Text(s) => match literal::match_literal(s, remaining) {
+			Ok((rem, _)) => Ok(vec![rem]),
+			Err(_) => Err(Mismatch::new(i, Expected::Text(s), input, remaining)),
+		},
+		Segment::Capture(c) => {
+			let mut ends = Vec::new();
+			if !c.is_required() {
+				ends.push(remaining);
+			}
+			// Refusing every end point makes the capture report all of them.
+			if let Ok((rem, _)) = c.get_match(remaining, |rem| {
+				ends.push(rem);
+				false
+			}) {
+				ends.push(rem);
+			}
+			if ends.is_empty() {
+				Err(Mismatch::new(i, Expected::Capture(c), input, remaining))
+			} else {
+				Ok(ends)
+			}
+		}
+		Segment::Group(caps) | Segment::PriorityGroup(caps) => {
+			let list = match seg {
+				Segment::Group(_) => List::group(caps),
+				_ => List::priority(caps),
+			};
+			match list.furthest(remaining) {
+				(rem, None) => Ok(vec![rem]),
+				(rem, Some(name)) => {
+					let c = caps.iter().find(|c| c.name == name).expect("the capture is in the group");
+					Err(Mismatch::new(i, Expected::Capture(c), input, rem))
+				}
+			}
+		}
+	};
+
+	let m = match ends {
+		Err(m) => Some(m),
+		Ok(ends) => ends
+			.into_iter()
+			.filter_map(|rem| furthest_mismatch(segs, i + 1, input, rem, seen))
+			.max_by_key(|m| (m.position, m.segment)),
+	};
+	seen.insert(key, m.clone());
+	m
+}
+
+impl fmt::Display for Expected<'_> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Text(s) => write!(f, "`{}`", s),
+			Self::Capture(c) => write!(f, "<{}>", c.name),
+		}
+	}
+}
+
+impl fmt::Display for Mismatch<'_, '_> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self.found {
+			Some(s) => write!(f, "expected {} at `{}`", self.expected, s),
+			None => write!(f, "expected {} but the input ended", self.expected),
+		}
+	}
+}
@@ -0,0 +1,66 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2024 Taylan Gökkaya
+
+// This file is licensed under the terms of Apache-2.0 License.
+
+use super::Segments;
+use crate::{
+	ast::{
+		Capture,
+		Segment,
+	},
+	Args,
+	Command,
+};
+
+/// The longest prefix of a [Command] that matched a text, returned by
+/// [Command::partial_match].
+///
+/// Lifetime `'c` refers to the command and `'t` refers to the text that was
+/// matched.
+#[derive(Clone, Debug)]
+pub struct PartialMatch<'c, 't> {
+	/// The captures of the segments that matched. [Args::rest] is the text
+	/// after them.
+	pub args: Args<'c, 't>,
+	/// The segments that are not satisfied, starting with the one that failed
+	/// to match. Empty if the whole command matched.
+	pub unmatched: &'c [Segment],
+}
+
+impl<'c> PartialMatch<'c, '_> {
+	/// Returns `true` if every segment matched.
+	pub fn is_complete(&self) -> bool {
+		self.unmatched.is_empty()
+	}
+
+	/// Returns an iterator over the captures in the unmatched segments that
+	/// must match for the command to match.
+	pub fn missing(&self) -> impl Iterator<Item = &'c Capture> {
+		self.unmatched
+			.iter()
+			.flat_map(|seg| seg.captures())
+			.filter(|c| c.is_required())
+	}
+}
+
+impl Command {
+	/// Matches as many segments of `self` as possible, in order.
+	/// #### Examples
+	/// ```rust
+	/// let cmd = malachi::Command::new("?mute <user> <duration: /^\\d+[mhd]$/>")?;
+	/// let partial = cmd.partial_match("?mute bob");
+	///
+	/// assert_eq!(Some("bob"), partial.args.get_once("user"));
+	/// let missing: Vec<_> = partial.missing().map(|c| c.name.as_str()).collect();
+	/// assert_eq!(vec!["duration"], missing);
+	/// # Ok::<(), malachi::Error>(())
+	/// ```
+	pub fn partial_match<'c, 't>(&'c self, input: &'t str) -> PartialMatch<'c, 't> {
+		let (args, failed) = Segments(&self.segments).match_prefix(input);
+		PartialMatch {
+			args,
+			unmatched: &self.segments[failed.unwrap_or(self.segments.len())..],
+		}
+	}
+}
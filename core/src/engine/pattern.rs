@@ -0,0 +1,413 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2024 Taylan Gökkaya
+
+// This file is licensed under the terms of Apache-2.0 License.
+
+use super::{
+	err,
+	IResult,
+	Value,
+};
+use regex::{
+	Captures,
+	Regex,
+};
+
+use crate::{
+	args::Groups,
+	ast::Pattern,
+	parser::prelude::*,
+};
+
+/// Takes a whitespace delimited word.
+pub fn word<'c, 'a>(input: &'a str) -> IResult<&'a str, Value<'c, 'a>> {
+	map(
+		verify(
+			preceded(multispace0, take_till(|c: char| c.is_whitespace())),
+			|s: &str| !s.is_empty(),
+		),
+		Value::from,
+	)(input)
+}
+
+pub fn any_of<'a, 'c>(
+	patterns: &'c [Pattern],
+) -> impl 'c + FnMut(&'a str) -> IResult<&'a str, Value<'c, 'a>> {
+	move |input: &'a str| {
+		for p in patterns {
+			if let Ok((rest, mut val)) = p.parse(input) {
+				if let Pattern::Delimited { escapes, .. } = p {
+					val.escapes = escapes;
+				}
+				return Ok((rest, val));
+			}
+		}
+		err!()
+	}
+}
+
+impl Pattern {
+	pub(crate) fn parse<'c, 'a>(&'c self, input: &'a str) -> IResult<&'a str, Value<'c, 'a>> {
+		match self {
+			Self::Word { reg } => {
+				// Take a space delimited word.
+				let (rest, text) = verify(
+					preceded(multispace0, take_while(|c: char| !c.is_whitespace())),
+					|s: &str| !s.is_empty(),
+				)(input)?;
+				match validate(reg, text) {
+					Some(groups) => Ok((rest, Value {
+						text,
+						groups,
+						escapes: &[],
+					})),
+					None => err!(),
+				}
+			}
+			Self::Eq { any_of, no_case } => {
+				for s in any_of {
+					let res: IResult<&'a str, &'a str> = if *no_case {
+						preceded(multispace0, tag_no_case(s.as_str()))(input)
+					} else {
+						preceded(multispace0, tag(s.as_str()))(input)
+					};
+
+					if let Ok((rest, s)) = res {
+						return Ok((rest, s.into()));
+					}
+				}
+				err!()
+			}
+			Self::CodeBlock {
+				langs,
+				reg,
+				no_case,
+			} => {
+				let input = input.trim_start();
+				let (rest, lang, code) = if let Some(body) = input.strip_prefix("```") {
+					let end = match body.find("```") {
+						Some(i) => i,
+						None => return err!(),
+					};
+					let block = &body[..end];
+					// The language is the first line, if it's a single word. An
+					// empty first line only ends the fence.
+					let (lang, code) = match block.split_once('\n') {
+						Some((lang, code)) if lang.trim().is_empty() => (None, code),
+						Some((lang, code))
+							if !lang.trim_end_matches('\r').contains(char::is_whitespace) =>
+						{
+							(Some(lang.trim_end_matches('\r')), code)
+						}
+						_ => (None, block),
+					};
+					(&body[end + 3..], lang, code)
+				} else {
+					// Inline code, delimited by one or two backticks.
+					let delim = if input.starts_with("``") { "``" } else { "`" };
+					let body = match input.strip_prefix(delim) {
+						Some(s) => s,
+						None => return err!(),
+					};
+					let end = match body.find(delim) {
+						Some(i) => i,
+						None => return err!(),
+					};
+					(&body[end + delim.len()..], None, &body[..end])
+				};
+
+				let lang_ok = langs.is_empty()
+					|| lang.is_some_and(|lang| {
+						langs.iter().any(|s| {
+							if *no_case {
+								s.eq_ignore_ascii_case(lang)
+							} else {
+								s == lang
+							}
+						})
+					});
+				if code.is_empty() || !lang_ok {
+					return err!();
+				}
+				let reg_groups = match validate(reg, code) {
+					Some(groups) => groups,
+					None => return err!(),
+				};
+
+				let whole = &input[..input.len() - rest.len()];
+				let mut groups = vec![(None, Some(whole))];
+				// Group 0 of the regex is the code itself.
+				groups.extend(reg_groups.0.into_iter().skip(1));
+				// The language goes last so it doesn't shift the regex groups.
+				groups.push((Some("lang"), lang));
+				Ok((
+					rest,
+					Value {
+						text: code,
+						groups: Groups(groups, 1),
+						escapes: &[],
+					},
+				))
+			}
+			Self::Extract { reg } => {
+				let input = input.trim_start();
+				for r in reg {
+					let (len, groups) = if r.captures_len() <= 1 {
+						match r.find(input) {
+							Some(m) => (m.end(), Groups::default()),
+							None => continue,
+						}
+					} else {
+						match r.captures(input) {
+							Some(caps) => (caps.get(0).map_or(0, |m| m.end()), groups_of(r, &caps)),
+							None => continue,
+						}
+					};
+					if len > 0 {
+						return Ok((
+							&input[len..],
+							Value {
+								text: &input[..len],
+								groups,
+								escapes: &[],
+							},
+						));
+					}
+				}
+				err!()
+			}
+			Self::Delimited {
+				starts,
+				ends,
+				reg,
+				no_case,
+				no_trim,
+				balanced,
+				quotes,
+				escapes,
+			} => {
+				let input = input.trim_start();
+
+				if starts.is_empty() {
+					for s in ends {
+						let body = Body {
+							start: None,
+							end: s.as_str(),
+							quotes: &[],
+							escapes,
+							no_case: *no_case,
+						};
+						let res: IResult<&'a str, &'a str> =
+							verify(|s| body.take(s), |s: &str| !s.is_empty())(input);
+
+						match res {
+							Err(_) => (),
+							Ok((rest, capture)) if !*no_trim => {
+								if let Some(groups) = validate(reg, capture) {
+									return Ok((rest, Value {
+										text: capture,
+										groups,
+										escapes: &[],
+									}));
+								}
+							}
+							Ok((rest, _)) => {
+								let capture = &input[..input.len() - rest.len()];
+								if let Some(groups) = validate(reg, capture) {
+									return Ok((rest, Value {
+										text: capture,
+										groups,
+										escapes: &[],
+									}));
+								}
+							}
+						}
+					}
+
+					err!()
+				} else if ends.is_empty() {
+					for s in starts {
+						let body = take_while(|c: char| !c.is_whitespace());
+						let res: IResult<&'a str, &'a str> = if *no_case {
+							let prefix = tag_no_case(s.as_str());
+							verify(preceded(prefix, body), |s: &str| !s.is_empty())(input)
+						} else {
+							let prefix = tag(s.as_str());
+							verify(preceded(prefix, body), |s: &str| !s.is_empty())(input)
+						};
+
+						match res {
+							Err(_) => (),
+							Ok((rest, capture)) if !*no_trim => {
+								if let Some(groups) = validate(reg, capture) {
+									return Ok((rest, Value {
+										text: capture,
+										groups,
+										escapes: &[],
+									}));
+								}
+							}
+							Ok((rest, _)) => {
+								let capture = input[..input.len() - rest.len()].trim_end();
+								if let Some(groups) = validate(reg, capture) {
+									return Ok((rest, Value {
+										text: capture,
+										groups,
+										escapes: &[],
+									}));
+								}
+							}
+						}
+					}
+
+					err!()
+				} else {
+					for start in starts {
+						for end in ends {
+							let right = tag(end.as_str());
+							let body = Body {
+								start: balanced.then_some(start.as_str()),
+								end: end.as_str(),
+								quotes: if *balanced { quotes } else { &[] },
+								escapes,
+								no_case: *no_case,
+							};
+							let body = |s| body.take(s);
+
+							let res: IResult<&'a str, &'a str> = if *no_case {
+								let left = tag_no_case(start.as_str());
+								delimited(left, body, right)(input)
+							} else {
+								let left = tag(start.as_str());
+								delimited(left, body, right)(input)
+							};
+
+							match res {
+								Err(_) => (),
+								Ok((rest, capture)) if !*no_trim => {
+									if let Some(groups) = validate(reg, capture) {
+										return Ok((rest, Value {
+											text: capture,
+											groups,
+											escapes: &[],
+										}));
+									}
+								}
+								Ok((rest, _)) => {
+									let capture = input[..input.len() - rest.len()].trim();
+									if let Some(groups) = validate(reg, capture) {
+										return Ok((rest, Value {
+											text: capture,
+											groups,
+											escapes: &[],
+										}));
+									}
+								}
+							}
+						}
+					}
+
+					err!()
+				}
+			}
+		}
+	}
+}
+
+/// Validates `s` with any of `regs`, returning the groups of the first regex
+/// that matches.
+///
+/// Regexes without any capture groups produce no groups.
+fn validate<'c, 't>(regs: &'c [Regex], s: &'t str) -> Option<Groups<'c, 't>> {
+	if regs.is_empty() {
+		return Some(Groups::default());
+	}
+
+	regs.iter().find_map(|r| {
+		if r.captures_len() <= 1 {
+			return r.is_match(s).then(Groups::default);
+		}
+		r.captures(s).map(|caps| groups_of(r, &caps))
+	})
+}
+
+fn groups_of<'c, 't>(r: &'c Regex, caps: &Captures<'t>) -> Groups<'c, 't> {
+	Groups(
+		r.capture_names()
+			.zip(caps.iter())
+			.map(|(name, m)| (name, m.map(|m| m.as_str())))
+			.collect(),
+		0,
+	)
+}
+
+/// Options for scanning the body of a [Pattern::Delimited].
+struct Body<'p> {
+	/// The opening delimiter, only set when nesting is tracked.
+	start: Option<&'p str>,
+	end: &'p str,
+	quotes: &'p [String],
+	escapes: &'p [String],
+	no_case: bool,
+}
+
+impl Body<'_> {
+	/// Takes input until the first `end` that is not escaped.
+	/// If `start` is set, nested pairs of `start` and `end` are skipped, so are
+	/// any delimiters inside `quotes`.
+	fn take<'a>(&self, input: &'a str) -> IResult<&'a str, &'a str> {
+		let mut depth = 0_usize;
+		let mut quote: Option<&str> = None;
+		let mut i = 0;
+
+		while i < input.len() {
+			let rest = &input[i..];
+			if let Some(esc) = self
+				.escapes
+				.iter()
+				.find(|e| !e.is_empty() && rest.starts_with(e.as_str()))
+			{
+				// Skip the escape and the character it escapes.
+				i += esc.len();
+				i += input[i..].chars().next().map_or(0, char::len_utf8);
+				continue;
+			} else if let Some(q) = quote {
+				if rest.starts_with(q) {
+					quote = None;
+					i += q.len();
+					continue;
+				}
+			} else if let Some(q) = self.quotes.iter().find(|q| rest.starts_with(q.as_str())) {
+				quote = Some(q);
+				i += q.len();
+				continue;
+			} else if rest.starts_with(self.end) {
+				if depth == 0 {
+					return Ok((rest, &input[..i]));
+				}
+				depth -= 1;
+				i += self.end.len();
+				continue;
+			} else if let Some(start) = self.start {
+				let open: IResult<&'a str, &'a str> = if self.no_case {
+					tag_no_case(start)(rest)
+				} else {
+					tag(start)(rest)
+				};
+				match open {
+					// Do not get stuck on an empty `start`.
+					Ok((_, s)) if !s.is_empty() => {
+						depth += 1;
+						i += s.len();
+						continue;
+					}
+					_ => (),
+				}
+			}
+
+			i += rest.chars().next().map_or(1, char::len_utf8);
+		}
+
+		err!()
+	}
+}
@@ -8,5 +8,8 @@ pub use crate::{
 		FilterError,
 		RuleError,
 	},
-	parser::SyntaxError,
+	parser::{
+		Location,
+		SyntaxError,
+	},
 };
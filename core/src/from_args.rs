@@ -0,0 +1,169 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2024 Taylan Gökkaya
+
+// This file is licensed under the terms of Apache-2.0 License.
+
+use std::{
+	error::Error,
+	fmt,
+	str::FromStr,
+};
+
+use crate::{
+	Args,
+	Command,
+	Match,
+};
+
+/// A type that can be built from the captures of a [Command].
+///
+/// This is usually derived with `#[derive(FromArgs)]`, enabled by the `macros`
+/// feature, which maps every field to the capture with the same name:
+/// `Option<T>` fields to captures that can be missing, `Vec<T>` fields to
+/// captures with the `*` or the `+` quantifiers and any other type to captures
+/// that must match. Values are converted with [FromStr].
+///
+/// A field can use a different capture with `#[malachi(name = "...")]`. If the
+/// struct has the `#[malachi(command = "...")]` attribute, the derive checks
+/// at compile time that the captures exist and have fitting quantifiers, and
+/// generates a `command()` function returning the compiled command.
+/// #### Examples
+/// ```rust
+/// use malachi::{
+/// 	Args,
+/// 	FromArgs,
+/// 	FromArgsError,
+/// };
+///
+/// struct Ban {
+/// 	user: u64,
+/// 	reason: Option<String>,
+/// }
+///
+/// impl FromArgs for Ban {
+/// 	fn from_args(args: &Args) -> Result<Self, FromArgsError> {
+/// 		Ok(Self {
+/// 			user: args.parse_once("user")?.ok_or(FromArgsError::missing("user"))?,
+/// 			reason: args.parse_once("reason")?,
+/// 		})
+/// 	}
+/// }
+///
+/// let cmd = malachi::Command::new("?ban <user> <reason?>")?;
+/// let ban = cmd.parse::<Ban>("?ban 42").unwrap();
+/// assert_eq!(42, ban.user);
+/// assert_eq!(None, ban.reason);
+/// # Ok::<(), malachi::Error>(())
+/// ```
+pub trait FromArgs: Sized {
+	/// Builds `Self` from the captures in `args`.
+	fn from_args(args: &Args) -> Result<Self, FromArgsError>;
+}
+
+/// The error returned by [Command::parse] and [FromArgs::from_args].
+#[derive(Debug)]
+pub enum FromArgsError {
+	/// The command did not match the input.
+	NoMatch,
+	/// A capture required by the type did not match.
+	Missing {
+		/// The name of the capture.
+		capture: String,
+	},
+	/// A captured value could not be converted to the type of its field.
+	Invalid {
+		/// The name of the capture.
+		capture: String,
+		/// The value that failed to convert.
+		value: String,
+		/// The error returned by the conversion.
+		error: Box<dyn Error + Send + Sync>,
+	},
+}
+
+impl FromArgsError {
+	/// Returns a [FromArgsError::Missing] for the capture `name`.
+	pub fn missing(name: &str) -> Self {
+		Self::Missing {
+			capture: name.to_string(),
+		}
+	}
+}
+
+impl Error for FromArgsError {
+	fn source(&self) -> Option<&(dyn Error + 'static)> {
+		match self {
+			Self::Invalid { error, .. } => Some(error.as_ref()),
+			_ => None,
+		}
+	}
+}
+
+impl fmt::Display for FromArgsError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::NoMatch => f.write_str("the command did not match"),
+			Self::Missing { capture } => write!(f, "missing value for `{}`", capture),
+			Self::Invalid {
+				capture,
+				value,
+				error,
+			} => write!(f, "invalid value {:?} for `{}`: {}", value, capture, error),
+		}
+	}
+}
+
+impl Command {
+	/// Matches `input` and converts the captures into `T`.
+	///
+	/// Returns [FromArgsError::NoMatch] if `self` does not match `input`.
+	pub fn parse<T: FromArgs>(&self, input: &str) -> Result<T, FromArgsError> {
+		let args = self.get_matches(input).ok_or(FromArgsError::NoMatch)?;
+		T::from_args(&args)
+	}
+}
+
+impl Args<'_, '_> {
+	/// Converts the value of `name` with [FromStr] if `name` has matched and is
+	/// a capture that matches at most once.
+	///
+	/// See [Args::get_once].
+	pub fn parse_once<T>(&self, name: &str) -> Result<Option<T>, FromArgsError>
+	where
+		T: FromStr,
+		T::Err: Into<Box<dyn Error + Send + Sync>>,
+	{
+		match self.vals.get(name) {
+			Some(Match::Once(s)) => convert(name, s).map(Some),
+			_ => Ok(None),
+		}
+	}
+
+	/// Converts every value of `name` with [FromStr] if `name` has matched and
+	/// is a capture that can match multiple times. Returns an empty [Vec]
+	/// otherwise.
+	///
+	/// See [Args::get_many].
+	pub fn parse_many<T>(&self, name: &str) -> Result<Vec<T>, FromArgsError>
+	where
+		T: FromStr,
+		T::Err: Into<Box<dyn Error + Send + Sync>>,
+	{
+		match self.vals.get(name) {
+			Some(Match::Many(xs)) => xs.iter().map(|s| convert(name, s)).collect(),
+			_ => Ok(Vec::new()),
+		}
+	}
+}
+
+fn convert<T>(name: &str, s: &str) -> Result<T, FromArgsError>
+where
+	T: FromStr,
+	T::Err: Into<Box<dyn Error + Send + Sync>>,
+{
+	s.parse().map_err(|e: T::Err| FromArgsError::Invalid {
+		capture: name.to_string(),
+		value: s.to_string(),
+		error: e.into(),
+	})
+}
@@ -0,0 +1,145 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2024 Taylan Gökkaya
+
+// This file is licensed under the terms of Apache-2.0 License.
+
+#![allow(clippy::tabs_in_doc_comments, clippy::doc_lazy_continuation)]
+
+//! The parser, compiler and matcher of [malachi](https://docs.rs/malachi).
+//!
+//! Everything in this crate is re-exported by `malachi`, which is the crate
+//! to depend on.
+
+mod ambiguity;
+mod args;
+/// Syntactic elements of a [Command].
+pub mod ast;
+pub mod builder;
+mod compiler;
+mod diagnostic;
+mod engine;
+/// Various errors used by the [Error] type.
+pub mod errors;
+mod from_args;
+mod lint;
+mod options;
+mod parser;
+#[cfg(feature = "serde")]
+mod ser;
+#[cfg(test)]
+mod tests;
+mod usage;
+
+use std::fmt;
+
+pub use ambiguity::{
+	Ambiguity,
+	AmbiguityReport,
+};
+pub use args::{
+	Args,
+	Groups,
+	Match,
+	OwnedArgs,
+	OwnedGroups,
+	OwnedMatch,
+};
+pub use compiler::Command;
+pub use diagnostic::{
+	Diagnostic,
+	Severity,
+};
+pub use engine::{
+	Expected,
+	FindIter,
+	Mismatch,
+	PartialMatch,
+};
+pub use from_args::{
+	FromArgs,
+	FromArgsError,
+};
+pub use lint::{
+	Lint,
+	Modifier,
+	Warning,
+};
+pub use options::{
+	CompileOptions,
+	Limit,
+	Overflow,
+};
+pub use usage::UsageStyle;
+use errors::*;
+
+/// Result with `E` being [Error].
+pub type Result<T, E = Error> = ::std::result::Result<T, E>;
+
+/// A command compilation error.
+#[derive(Debug, Clone)]
+pub enum Error {
+	/// Returned when the command fails to parse.
+	Syntax(SyntaxError),
+	/// Returned when a command has an invalid filter, along with where the
+	/// offending filter or pattern is in the source.
+	Filter(FilterError, Option<Location>),
+	/// Returned when a command fails to compile, along with where the offending
+	/// segment is in the source. This usually means there is no way to get
+	/// linear time matching.
+	Rule(RuleError, Option<Location>),
+}
+
+impl Error {
+	/// Returns where the error is in the source of the command.
+	///
+	/// Only errors from commands compiled from source have a location.
+	pub fn location(&self) -> Option<Location> {
+		match self {
+			Self::Syntax(e) => Some(Location {
+				span: e.span.clone(),
+				line_no: e.line_no,
+				col: e.col,
+			}),
+			Self::Filter(_, location) | Self::Rule(_, location) => location.clone(),
+		}
+	}
+}
+
+impl std::error::Error for Error {}
+impl fmt::Display for Error {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Syntax(e) => fmt::Display::fmt(&e, f),
+			Self::Filter(e, location) => {
+				if let Some(loc) = location {
+					write!(f, "{}: ", loc)?;
+				}
+				fmt::Display::fmt(&e, f)
+			}
+			Self::Rule(e, location) => {
+				if let Some(loc) = location {
+					write!(f, "{}: ", loc)?;
+				}
+				fmt::Display::fmt(&e, f)
+			}
+		}
+	}
+}
+
+impl From<SyntaxError> for Error {
+	fn from(e: SyntaxError) -> Self {
+		Self::Syntax(e)
+	}
+}
+
+impl From<RuleError> for Error {
+	fn from(e: RuleError) -> Self {
+		Self::Rule(e, None)
+	}
+}
+
+impl From<FilterError> for Error {
+	fn from(e: FilterError) -> Self {
+		Self::Filter(e, None)
+	}
+}
@@ -0,0 +1,263 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2024 Taylan Gökkaya
+
+// This file is licensed under the terms of Apache-2.0 License.
+
+use std::fmt;
+
+use crate::{
+	ast::{
+		Capture,
+		Pattern,
+		Segment,
+	},
+	errors::Location,
+	parser::{
+		self,
+		Quantifier,
+	},
+	Command,
+	Diagnostic,
+	Severity,
+};
+
+/// Something in a command that compiles but probably does not behave as
+/// intended.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub enum Warning {
+	/// Two optional captures without patterns in the same group `{}`; they
+	/// accept the same words so `first` always takes a word before `second`.
+	OptionalCaptures { first: String, second: String },
+	/// An `eq` alternative that starts with an earlier alternative of the same
+	/// capture, so it can never match in full.
+	ShadowedEq {
+		capture: String,
+		/// The alternative that can't match.
+		shadowed: String,
+		/// The earlier alternative that matches instead.
+		by: String,
+	},
+	/// A filter that has no effect on its pattern, like `notrim` without
+	/// `starts` or `ends`, or `nocase` with only a `regex`.
+	IneffectiveFilter { capture: String, filter: Modifier },
+}
+
+/// A filter that only changes how the other filters of its pattern behave.
+#[derive(Clone, Copy, Eq, PartialEq, Debug, Hash)]
+pub enum Modifier {
+	/// The `nocase` filter.
+	NoCase,
+	/// The `notrim` filter.
+	NoTrim,
+}
+
+/// A [Warning] along with where it is in the source.
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Lint {
+	pub warning: Warning,
+	/// Where the offending segment or pattern is in the source, if the command
+	/// was compiled from source.
+	pub location: Option<Location>,
+}
+
+impl Command {
+	/// Returns warnings about parts of `self` that probably don't behave as
+	/// intended.
+	/// #### Examples
+	/// ```rust
+	/// use malachi::Warning;
+	///
+	/// let cmd = malachi::Command::new("?play <mode: eq('p', 'play')>")?;
+	/// let lints = cmd.lint();
+	///
+	/// assert_eq!(lints.len(), 1);
+	/// assert!(matches!(
+	/// 	&lints[0].warning,
+	/// 	Warning::ShadowedEq { shadowed, by, .. } if shadowed == "play" && by == "p",
+	/// ));
+	/// # Ok::<(), malachi::Error>(())
+	/// ```
+	pub fn lint(&self) -> Vec<Lint> {
+		let mut lints = Vec::new();
+		let mut locations = vec![None; self.segments.len()];
+		if let Some((s, segments)) = self.parse_source() {
+			for (i, (src, seg)) in segments.iter().enumerate() {
+				locations[i] = Some(Location::of(s, src));
+				lints.extend(lint_source(seg).into_iter().map(|(warning, src)| Lint {
+					warning,
+					location: Some(Location::of(s, src)),
+				}));
+			}
+		}
+
+		for (seg, location) in self.segments.iter().zip(locations) {
+			lints.extend(seg.lint().into_iter().map(|warning| Lint {
+				warning,
+				location: location.clone(),
+			}));
+		}
+
+		lints.sort_by_key(|l| l.location.as_ref().map_or(usize::MAX, |l| l.span.start));
+		lints
+	}
+}
+
+impl Segment {
+	fn lint(&self) -> Vec<Warning> {
+		let mut warnings = Vec::new();
+		for c in self.captures() {
+			c.lint_eq(&mut warnings);
+		}
+
+		if let Self::Group(cs) = self {
+			let optional = cs.iter().filter(|c| c.is_optional_word()).collect::<Vec<_>>();
+			for (i, first) in optional.iter().enumerate() {
+				for second in &optional[i + 1..] {
+					warnings.push(Warning::OptionalCaptures {
+						first: first.name.clone(),
+						second: second.name.clone(),
+					});
+				}
+			}
+		}
+
+		warnings
+	}
+}
+
+impl Capture {
+	/// Returns true if `self` is optional and matches any word.
+	fn is_optional_word(&self) -> bool {
+		matches!(self.quantifier, Quantifier::MaybeOnce | Quantifier::Many0)
+			&& self
+				.patterns
+				.iter()
+				.all(|p| matches!(p, Pattern::Word { reg } if reg.is_empty()))
+	}
+
+	fn lint_eq(&self, warnings: &mut Vec<Warning>) {
+		let mut seen: Vec<(&str, bool)> = Vec::new();
+		for p in &self.patterns {
+			let Pattern::Eq { any_of, no_case } = p else {
+				continue;
+			};
+			for s in any_of {
+				let by = seen.iter().find(|&&(x, x_no_case)| {
+					if x_no_case {
+						s.to_lowercase().starts_with(&x.to_lowercase())
+					} else {
+						s.starts_with(x)
+					}
+				});
+				if let Some((by, _)) = by {
+					warnings.push(Warning::ShadowedEq {
+						capture: self.name.clone(),
+						shadowed: s.clone(),
+						by: by.to_string(),
+					});
+				}
+				seen.push((s, *no_case));
+			}
+		}
+	}
+}
+
+/// Finds warnings that can't be found after compilation, returning them along
+/// with the source of the offending pattern.
+fn lint_source<'a>(seg: &parser::Segment<'a>) -> Vec<(Warning, &'a str)> {
+	let caps = match seg {
+		parser::Segment::Text(_) => return Vec::new(),
+		parser::Segment::Capture(c) => std::slice::from_ref(c),
+		parser::Segment::Group(cs) | parser::Segment::PriorityGroup(cs) => cs.as_slice(),
+	};
+
+	let mut warnings = Vec::new();
+	for c in caps {
+		for p in &c.patterns {
+			let has = |names: &[&str]| p.filters.iter().any(|f| names.contains(&f.name));
+			let mut ineffective = |filter: Modifier| {
+				let w = Warning::IneffectiveFilter {
+					capture: c.name.to_string(),
+					filter,
+				};
+				warnings.push((w, p.src));
+			};
+
+			if has(&["notrim"]) && !has(&["starts", "ends"]) {
+				ineffective(Modifier::NoTrim);
+			}
+			if has(&["nocase"]) && !has(&["eq", "starts", "ends", "codeblock"]) {
+				ineffective(Modifier::NoCase);
+			}
+		}
+	}
+
+	warnings
+}
+
+impl fmt::Display for Warning {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::OptionalCaptures { first, second } => write!(
+				f,
+				"`{}` and `{}` are optional and accept any word; `{}` only gets a value after `{}` is filled",
+				first, second, second, first
+			),
+			Self::ShadowedEq {
+				capture,
+				shadowed,
+				by,
+			} => write!(
+				f,
+				"`{}` in `{}` can never match because `{}` matches first",
+				shadowed, capture, by
+			),
+			Self::IneffectiveFilter {
+				capture,
+				filter: Modifier::NoTrim,
+			} => write!(
+				f,
+				"the `notrim` filter in `{}` has no effect without `starts` or `ends`",
+				capture
+			),
+			Self::IneffectiveFilter {
+				capture,
+				filter: Modifier::NoCase,
+			} => write!(
+				f,
+				"the `nocase` filter in `{}` has no effect here; regexes need the `(?i)` flag",
+				capture
+			),
+		}
+	}
+}
+
+impl fmt::Display for Modifier {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		f.write_str(match self {
+			Self::NoCase => "nocase",
+			Self::NoTrim => "notrim",
+		})
+	}
+}
+
+impl fmt::Display for Lint {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		if let Some(loc) = &self.location {
+			write!(f, "{}: ", loc)?;
+		}
+		fmt::Display::fmt(&self.warning, f)
+	}
+}
+
+impl From<Lint> for Diagnostic {
+	fn from(l: Lint) -> Self {
+		Self {
+			severity: Severity::Warning,
+			message: l.warning.to_string(),
+			location: l.location,
+			hint: None,
+			error: None,
+		}
+	}
+}
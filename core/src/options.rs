@@ -0,0 +1,160 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2024 Taylan Gökkaya
+
+// This file is licensed under the terms of Apache-2.0 License.
+
+use std::collections::HashMap;
+
+use crate::{
+	ast::{
+		Capture,
+		Segment,
+	},
+	Command,
+};
+
+/// What a capture does when it could match more values than its [Limit].
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(rename_all = "snake_case")
+)]
+pub enum Overflow {
+	/// Stop at the limit and leave the rest of the values to the following
+	/// segments or [Args::rest][crate::Args::rest].
+	#[default]
+	Truncate,
+	/// Do not match at all.
+	Fail,
+}
+
+/// The maximum number of values a capture with the `*` or the `+` quantifier
+/// can match.
+///
+/// The default is 51 values with [Overflow::Truncate], the number of values
+/// kept before limits could be configured.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(try_from = "crate::ser::LimitRepr")
+)]
+pub struct Limit {
+	max: usize,
+	overflow: Overflow,
+}
+
+/// Options for [Command::with_options].
+/// #### Examples
+/// ```rust
+/// use malachi::{
+/// 	CompileOptions,
+/// 	Command,
+/// 	Limit,
+/// };
+///
+/// let opts = CompileOptions::new()
+/// 	.limit(Limit::fail(3))
+/// 	.capture_limit("tags", Limit::truncate(2));
+/// let cmd = Command::with_options("?add <tags*: starts('#')> <nums+>", &opts)?;
+///
+/// let args = cmd.get_matches("?add #a #b #c 1 2").unwrap();
+/// assert_eq!(Some(&vec!["a", "b"]), args.get_many("tags"));
+/// assert_eq!(Some(&vec!["#c", "1", "2"]), args.get_many("nums"));
+///
+/// assert_eq!(None, cmd.get_matches("?add 1 2 3 4"));
+/// # Ok::<(), malachi::Error>(())
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct CompileOptions {
+	limit: Limit,
+	capture_limits: HashMap<String, Limit>,
+}
+
+impl Limit {
+	/// Limits the capture to `max` values, ignoring any more. `max` is at
+	/// least 1.
+	pub fn truncate(max: usize) -> Self {
+		Self {
+			max: max.max(1),
+			overflow: Overflow::Truncate,
+		}
+	}
+
+	/// Limits the capture to `max` values, failing the match if the capture
+	/// could match more. `max` is at least 1.
+	pub fn fail(max: usize) -> Self {
+		Self {
+			max: max.max(1),
+			overflow: Overflow::Fail,
+		}
+	}
+
+	/// Returns the maximum number of values.
+	pub fn max(&self) -> usize {
+		self.max
+	}
+
+	/// Returns what happens when the capture could match more than
+	/// [Limit::max] values.
+	pub fn overflow(&self) -> Overflow {
+		self.overflow
+	}
+
+	#[cfg_attr(not(feature = "serde"), allow(dead_code))]
+	pub(crate) fn is_default(&self) -> bool {
+		*self == Self::default()
+	}
+}
+
+impl Default for Limit {
+	fn default() -> Self {
+		Self::truncate(51)
+	}
+}
+
+impl CompileOptions {
+	/// Returns the default options.
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Sets the [Limit] of every capture.
+	pub fn limit(mut self, limit: Limit) -> Self {
+		self.limit = limit;
+		self
+	}
+
+	/// Sets the [Limit] of the capture `name`, overriding
+	/// [CompileOptions::limit].
+	///
+	/// Limits of captures the command does not have are ignored.
+	pub fn capture_limit(mut self, name: impl Into<String>, limit: Limit) -> Self {
+		self.capture_limits.insert(name.into(), limit);
+		self
+	}
+}
+
+impl Command {
+	/// Compiles a command like [Command::new], with `options`.
+	pub fn with_options(s: &str, options: &CompileOptions) -> crate::Result<Self> {
+		let mut cmd = Self::new(s)?;
+		for c in cmd.captures_mut() {
+			c.limit = options
+				.capture_limits
+				.get(&c.name)
+				.copied()
+				.unwrap_or(options.limit);
+		}
+		Ok(cmd)
+	}
+
+	pub(crate) fn captures_mut(&mut self) -> impl Iterator<Item = &mut Capture> {
+		self.segments.iter_mut().flat_map(|seg| match seg {
+			Segment::Text(_) => &mut [],
+			Segment::Capture(c) => std::slice::from_mut(c),
+			Segment::Group(cs) | Segment::PriorityGroup(cs) => cs.as_mut_slice(),
+		})
+	}
+}
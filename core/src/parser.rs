@@ -0,0 +1,374 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2024 Taylan Gökkaya
+
+// This file is licensed under the terms of Apache-2.0 License.
+
+mod capture;
+mod command;
+mod comment;
+mod filter;
+mod literal;
+pub mod prelude;
+mod string;
+#[cfg(test)]
+mod tests;
+
+use std::{
+	fmt,
+	ops::Range,
+};
+
+pub use command::{
+	parse_command,
+	parse_command_recovering,
+	Segments,
+};
+use nom::{
+	error::{
+		VerboseError,
+		VerboseErrorKind,
+	},
+	Offset,
+};
+
+#[derive(Clone, Eq, Debug)]
+pub struct Filter<'a> {
+	pub name: &'a str,
+	pub args: Vec<String>,
+	/// True if any of `args` is a regex literal, which only `extract` and
+	/// `regex` accept.
+	pub regex_args: bool,
+	/// The source text of the filter, used for error locations.
+	pub src: &'a str,
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct Capture<'a> {
+	pub name: &'a str,
+	pub quantifier: Quantifier,
+	pub patterns: Vec<Pattern<'a>>,
+	/// The text of the doc comments before the capture.
+	pub description: Option<String>,
+}
+
+#[derive(Clone, Debug, Eq)]
+pub struct Pattern<'a> {
+	pub filters: Vec<Filter<'a>>,
+	/// The source text of the pattern, used for error locations.
+	pub src: &'a str,
+}
+
+// The source text is not compared.
+impl PartialEq for Filter<'_> {
+	fn eq(&self, other: &Self) -> bool {
+		self.name == other.name && self.args == other.args
+	}
+}
+
+impl PartialEq for Pattern<'_> {
+	fn eq(&self, other: &Self) -> bool {
+		self.filters == other.filters
+	}
+}
+
+/// Represents a quantifier.
+///
+/// Quantifiers signal a capture how many times they can/must match.
+/// In a capture, if a quantifier is omitted, [Once][Self::Once] is used.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Hash)]
+#[cfg_attr(
+	feature = "serde",
+	derive(serde::Serialize, serde::Deserialize),
+	serde(rename_all = "snake_case")
+)]
+pub enum Quantifier {
+	/// Must match exactly once. The default if not specified.
+	Once,
+	/// Can match 0 or 1 times. Corresponds to the '?' symbol.
+	MaybeOnce,
+	/// Can match any number of times including 0. Corresponds to the '*'
+	/// symbol.
+	Many0,
+	/// Must match at least once, potentially more. Corresponds to the '+'
+	/// symbol.
+	Many1,
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum Segment<'a> {
+	Text(String),
+	Capture(Capture<'a>),
+	PriorityGroup(Vec<Capture<'a>>),
+	Group(Vec<Capture<'a>>),
+}
+
+impl<'a> fmt::Display for Segment<'a> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self {
+			Self::Text(s) => f.write_str(s),
+			Self::Capture(c) => write!(f, "{}", &c),
+			Self::PriorityGroup(cs) => {
+				if cs.is_empty() {
+					f.write_str("[]")
+				} else {
+					writeln!(f, "[")?;
+
+					for c in cs {
+						writeln!(f, "  {}", c)?;
+					}
+					f.write_str("]")
+				}
+			}
+			Self::Group(cs) => {
+				if cs.is_empty() {
+					f.write_str("{}")
+				} else {
+					f.write_str("{\n")?;
+
+					for c in cs {
+						writeln!(f, "  {}", c)?;
+					}
+					f.write_str("}")
+				}
+			}
+		}
+	}
+}
+
+impl<'a> fmt::Display for Capture<'a> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self.patterns.len() {
+			0 => write!(f, "<{}{}>", self.name, self.quantifier),
+			1 => write!(
+				f,
+				"<{}{}: {}>",
+				self.name, self.quantifier, &self.patterns[0]
+			),
+			_ => {
+				write!(f, "<\n  {}{}:\n", self.name, self.quantifier)?;
+				for p in &self.patterns {
+					writeln!(f, "  {};", p)?;
+				}
+				f.write_str(">")
+			}
+		}
+	}
+}
+
+impl<'a> fmt::Display for Filter<'a> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self.args.len() {
+			0 => write!(f, "{}()", self.name),
+			1 => write!(f, "{}({:?})", self.name, &self.args[0]),
+			_ => {
+				write!(f, "{}(", self.name)?;
+				for (i, a) in self.args.iter().enumerate() {
+					if i > 0 {
+						f.write_str(", ")?;
+					}
+					write!(f, "{:?}", a)?;
+				}
+				f.write_str(")")
+			}
+		}
+	}
+}
+
+impl<'a> fmt::Display for Pattern<'a> {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match self.filters.len() {
+			0 => Ok(()),
+			1 => write!(f, "{}", &self.filters[0]),
+			_ => {
+				for (i, x) in self.filters.iter().enumerate() {
+					if i > 0 {
+						f.write_str(", ")?;
+					}
+					write!(f, "{}", &x)?;
+				}
+				Ok(())
+			}
+		}
+	}
+}
+
+impl fmt::Display for Quantifier {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		match *self {
+			Self::Once => Ok(()),
+			Self::MaybeOnce => f.write_str("?"),
+			Self::Many0 => f.write_str("*"),
+			Self::Many1 => f.write_str("+"),
+		}
+	}
+}
+
+/// A position in the source text of a command.
+#[derive(Clone, Eq, PartialEq, Debug, Default, Hash)]
+pub struct Location {
+	/// The byte range in the source text.
+	pub span: Range<usize>,
+	/// The line number, starting from 1.
+	pub line_no: usize,
+	/// The column in characters, starting from 1.
+	pub col: usize,
+}
+
+impl Location {
+	/// Returns the location of `part`, which must be a slice of `src`.
+	pub(crate) fn of(src: &str, part: &str) -> Self {
+		let start = src.offset(part);
+		Self::new(src, start..start + part.len())
+	}
+
+	pub(crate) fn new(src: &str, span: Range<usize>) -> Self {
+		let before = &src[..span.start];
+		let line_start = before.rfind('\n').map_or(0, |i| i + 1);
+		Self {
+			line_no: before.matches('\n').count() + 1,
+			col: before[line_start..].chars().count() + 1,
+			span,
+		}
+	}
+}
+
+impl fmt::Display for Location {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}:{}", self.line_no, self.col)
+	}
+}
+
+#[derive(Clone, Eq, PartialEq, Debug)]
+pub struct SyntaxError {
+	/// The line number, starting from 1.
+	pub line_no: usize,
+	/// The column in characters, starting from 1.
+	pub col: usize,
+	/// The byte range of the offending text in the source.
+	pub span: Range<usize>,
+	/// The line the error is on.
+	pub line: String,
+	pub msg: &'static str,
+	/// Tokens that would have been valid at the error position.
+	pub expected: Vec<String>,
+	/// A suggestion on how to fix the error, if there is one.
+	pub hint: Option<&'static str>,
+}
+
+impl SyntaxError {
+	fn from_nom(e: VerboseError<&str>, s: &str) -> Self {
+		let (remaining, msg): (&str, &str) = e
+			.errors
+			.iter()
+			.filter_map(|(rem, kind)| match kind {
+				VerboseErrorKind::Context(msg) => Some((rem, msg)),
+				_ => None,
+			})
+			.next()
+			.map(|(&x, &y)| (x, y))
+			.unwrap_or_else(|| {
+				e.errors
+					.last()
+					.map(|(rem, _)| (*rem, "syntax error near"))
+					.unwrap_or_else(|| (s, "unidentified syntax error"))
+			});
+
+		let mut expected = Vec::new();
+		for (rem, kind) in &e.errors {
+			if let VerboseErrorKind::Char(c) = kind {
+				if rem.len() == remaining.len() && !expected.contains(&c.to_string()) {
+					expected.push(c.to_string());
+				}
+			}
+		}
+
+		// `alt` only keeps the last alternative, the hint lists all of them.
+		if msg == "invalid escape sequence" {
+			expected.clear();
+		}
+
+		let start = s.len() - remaining.len();
+		let len = remaining.chars().next().map_or(0, char::len_utf8);
+		let loc = Location::new(s, start..start + len);
+		let line = s.lines().nth(loc.line_no - 1).unwrap_or("").to_string();
+
+		Self {
+			line,
+			line_no: loc.line_no,
+			col: loc.col,
+			span: loc.span,
+			msg,
+			expected,
+			hint: hint(msg),
+		}
+	}
+}
+
+fn hint(msg: &str) -> Option<&'static str> {
+	match msg {
+		"invalid capture syntax" | "invalid identifier name" => Some(
+			"captures look like `<name>` or `<name: filter(\"arg\")>`; to match a literal `<`, escape it as `\\<`",
+		),
+		"missing closing delimiter: '>'" => {
+			Some("close the capture with `>`; inside it, filters are separated by `,` and patterns by `;`")
+		}
+		"missing closing delimiter: ']'" | "missing closing delimiter: '}'" => {
+			Some("groups can only contain captures")
+		}
+		"missing closing delimiter: ')'" => {
+			Some("filter arguments are quoted strings or regexes separated by `,`")
+		}
+		"invalid escape sequence" => Some(
+			"valid escape sequences in literals are `\\\\`, `\\n`, `\\r`, `\\t`, `\\<`, `\\[`, `\\{`, `\\/` and escaped spaces",
+		),
+		"unterminated string" => Some("quotes inside strings can be escaped with `\\`"),
+		"unterminated regex" => Some("`/` inside regexes can be escaped with `\\/`"),
+		_ => None,
+	}
+}
+
+impl SyntaxError {
+	/// Returns the message along with the expected tokens, without the
+	/// position.
+	pub(crate) fn message(&self) -> String {
+		let mut buf = String::from(self.msg);
+		match self.expected.as_slice() {
+			[] => (),
+			[x] => buf.push_str(&format!("; expected `{}`", x)),
+			xs => {
+				buf.push_str("; expected one of ");
+				for (i, x) in xs.iter().enumerate() {
+					if i > 0 {
+						buf.push_str(", ");
+					}
+					buf.push_str(&format!("`{}`", x));
+				}
+			}
+		}
+		buf
+	}
+}
+
+impl fmt::Display for SyntaxError {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		write!(f, "{}:{}: {}", self.line_no, self.col, self.message())?;
+
+		if !self.line.is_empty() && f.alternate() {
+			let mut pad = String::with_capacity(self.col);
+			pad.extend(
+				self.line
+					.chars()
+					.take(self.col.saturating_sub(1))
+					.map(|c| if c == '\t' { '\t' } else { ' ' }),
+			);
+			pad.push('^');
+			write!(f, "\n|\n| {}\n| {}", &self.line, pad)?;
+			if let Some(hint) = self.hint {
+				write!(f, "\n= help: {}", hint)?;
+			}
+		}
+		Ok(())
+	}
+}
+
+impl std::error::Error for SyntaxError {}
@@ -4,6 +4,11 @@
 // This file is licensed under the terms of Apache-2.0 License.
 
 use super::{
+	comment::{
+		join_docs,
+		parse_trivia,
+		Comment,
+	},
 	filter::parse_filter,
 	prelude::*,
 	Capture,
@@ -54,6 +59,7 @@ pub fn parse_capture(input: &'_ str) -> IResult<&'_ str, Capture<'_>> {
 		name,
 		quantifier,
 		patterns: vec![],
+		description: None,
 	});
 
 	let full = separated_pair(
@@ -62,13 +68,17 @@ pub fn parse_capture(input: &'_ str) -> IResult<&'_ str, Capture<'_>> {
 		// A colon, optionally wrapped by any number of whitespace.
 		wrap_space0(char(':')),
 		// A list of patterns, separated by a semicolon.
-		list0(parse_filters, ';'),
+		list0(consumed(parse_filters), ';'),
 	);
 
 	let full = map(full, |((name, quantifier), patterns)| Capture {
 		name,
 		quantifier,
-		patterns: patterns.into_iter().map(Pattern).collect(),
+		patterns: patterns
+			.into_iter()
+			.map(|(src, filters)| Pattern { filters, src })
+			.collect(),
+		description: None,
 	});
 
 	// A capture is`name+quantifier`, optionally followed by a semicolon and a space
@@ -86,8 +96,20 @@ pub fn parse_capture(input: &'_ str) -> IResult<&'_ str, Capture<'_>> {
 	)(input)
 }
 
+/// Parses a capture in a group along with the doc comments before it.
+fn parse_group_item(input: &'_ str) -> IResult<&'_ str, Capture<'_>> {
+	map(pair(parse_trivia, parse_capture), |(comments, mut c)| {
+		// Both kinds of doc comments document the capture inside groups.
+		c.description = join_docs(comments.into_iter().filter_map(|c| match c {
+			Comment::Doc(s) | Comment::CommandDoc(s) => Some(s),
+			Comment::Plain => None,
+		}));
+		c
+	})(input)
+}
+
 pub fn parse_priority_group(input: &'_ str) -> IResult<&'_ str, Vec<Capture<'_>>> {
-	let body = many0(wrap_space0(parse_capture));
+	let body = terminated(many0(parse_group_item), parse_trivia);
 	delimited(
 		// priority groups start with `[`.
 		char('['),
@@ -99,7 +121,7 @@ pub fn parse_priority_group(input: &'_ str) -> IResult<&'_ str, Vec<Capture<'_>>
 }
 
 pub fn parse_group(input: &'_ str) -> IResult<&'_ str, Vec<Capture<'_>>> {
-	let body = many0(wrap_space0(parse_capture));
+	let body = terminated(many0(parse_group_item), parse_trivia);
 	delimited(
 		// groups start with `{`.
 		char('{'),
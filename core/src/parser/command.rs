@@ -0,0 +1,143 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2024 Taylan Gökkaya
+
+// This file is licensed under the terms of Apache-2.0 License.
+
+use nom::Finish;
+
+use super::{
+	capture::{
+		parse_capture,
+		parse_group,
+		parse_priority_group,
+	},
+	comment::{
+		join_docs,
+		parse_trivia,
+		Comment,
+	},
+	literal::parse_literal,
+	prelude::*,
+	Segment,
+	SyntaxError,
+};
+
+pub fn parse_segment(input: &'_ str) -> IResult<&'_ str, Segment<'_>> {
+	alt((
+		// First try parsing a priority group  `[]`.
+		map(parse_priority_group, Segment::PriorityGroup),
+		// Or a normal group `{}`.
+		map(parse_group, Segment::Group),
+		// Or a single capture `<>`.
+		map(parse_capture, Segment::Capture),
+		// If all fails, it's a literal.
+		map(parse_literal, Segment::Text),
+	))(input)
+}
+
+/// Parses a command, returning its segments along with their source text and
+/// the description of the command.
+pub fn parse_command(input: &'_ str) -> Result<Parsed<'_>, SyntaxError> {
+	let (segments, description, mut errors) = parse_segments(input, false);
+	if errors.is_empty() {
+		Ok((segments.into_iter().flatten().collect(), description))
+	} else {
+		Err(errors.swap_remove(0))
+	}
+}
+
+/// Parses a command like [parse_command], but recovers from syntax errors and
+/// returns all of them.
+///
+/// Segments that failed to parse are `None`.
+pub fn parse_command_recovering(
+	input: &'_ str,
+) -> (Segments<'_>, Option<String>, Vec<SyntaxError>) {
+	parse_segments(input, true)
+}
+
+/// Parsed segments along with their source text.
+pub type Segments<'a> = Vec<Option<(&'a str, Segment<'a>)>>;
+
+/// Segments along with their source text, and the description of a command.
+pub type Parsed<'a> = (Vec<(&'a str, Segment<'a>)>, Option<String>);
+
+fn parse_segments(
+	input: &'_ str,
+	recover: bool,
+) -> (Segments<'_>, Option<String>, Vec<SyntaxError>) {
+	let mut segments = Vec::new();
+	let mut errors = Vec::new();
+	let mut description = Vec::new();
+
+	// Whitespace and comments can't fail to parse.
+	let trivia = |s| parse_trivia(s).unwrap_or((s, Vec::new()));
+	let (mut remaining, mut comments) = trivia(input);
+
+	while !remaining.is_empty() {
+		let mut docs = Vec::new();
+		for c in std::mem::take(&mut comments) {
+			match c {
+				Comment::Doc(s) => docs.push(s),
+				Comment::CommandDoc(s) => description.push(s),
+				Comment::Plain => (),
+			}
+		}
+
+		match consumed(parse_segment)(remaining).finish() {
+			Ok((rem, (src, mut seg))) => {
+				// Doc comments before anything other than a capture describe the
+				// command.
+				match &mut seg {
+					Segment::Capture(c) => c.description = join_docs(docs),
+					_ => description.extend(docs),
+				}
+				segments.push(Some((src, seg)));
+				(remaining, comments) = trivia(rem);
+			}
+			Err(e) => {
+				let e = SyntaxError::from_nom(e, input);
+				let pos = e.span.start.max(input.len() - remaining.len());
+				errors.push(e);
+				if !recover {
+					break;
+				}
+				segments.push(None);
+				(remaining, comments) = trivia(skip_erroneous(remaining, &input[pos..]));
+			}
+		}
+	}
+
+	for c in comments {
+		if let Comment::Doc(s) | Comment::CommandDoc(s) = c {
+			description.push(s);
+		}
+	}
+
+	(segments, join_docs(description), errors)
+}
+
+/// Returns the input after a segment that failed to parse.
+///
+/// `segment` is where the segment starts and `error` is where the error is.
+fn skip_erroneous<'a>(segment: &'a str, error: &'a str) -> &'a str {
+	let closing = match segment.chars().next() {
+		Some('<') => Some('>'),
+		Some('[') => Some(']'),
+		Some('{') => Some('}'),
+		_ => None,
+	};
+
+	// Skip to after the closing delimiter if there's one.
+	if let Some(i) = closing.and_then(|c| error.find(c)) {
+		return &error[i + 1..];
+	}
+	// Else skip the current line or word.
+	// `segment` does not start with whitespace so this always makes progress.
+	let end = if closing.is_some() {
+		error.find('\n')
+	} else {
+		error.find(char::is_whitespace)
+	};
+	end.map_or("", |i| &error[i..])
+}
@@ -0,0 +1,62 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2024 Taylan Gökkaya
+
+// This file is licensed under the terms of Apache-2.0 License.
+
+use super::prelude::*;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Comment<'a> {
+	/// `// ...`, ignored.
+	Plain,
+	/// `/// ...`, documents the next capture.
+	Doc(&'a str),
+	/// `//! ...`, documents the command.
+	CommandDoc(&'a str),
+}
+
+/// Returns `true` if the text after `//` starts a comment. `///` and `//!`
+/// always do; a plain `//` must be followed by whitespace or the end of the
+/// line, so that literals like `//roll` are not comments.
+fn is_comment(s: &str) -> bool {
+	s.chars()
+		.next()
+		.is_none_or(|c| c == '/' || c == '!' || c.is_whitespace())
+}
+
+fn parse_comment(input: &str) -> IResult<&str, Comment<'_>> {
+	let line = verify(take_till(|c| c == '\n'), |s: &str| is_comment(s));
+	map(preceded(tag("//"), line), |s: &str| {
+		let s = s.strip_suffix('\r').unwrap_or(s);
+		// Like in Rust, `////` is not a doc comment.
+		if let Some(s) = s.strip_prefix('!') {
+			Comment::CommandDoc(s)
+		} else if let Some(s) = s.strip_prefix('/').filter(|s| !s.starts_with('/')) {
+			Comment::Doc(s)
+		} else {
+			Comment::Plain
+		}
+	})(input)
+}
+
+/// Skips any whitespace and comments.
+pub fn parse_trivia(input: &str) -> IResult<&str, Vec<Comment<'_>>> {
+	preceded(
+		multispace0,
+		many0(delimited(multispace0, parse_comment, multispace0)),
+	)(input)
+}
+
+/// Joins the lines of doc comments, removing a single leading space from each
+/// line.
+pub fn join_docs<'a>(lines: impl IntoIterator<Item = &'a str>) -> Option<String> {
+	let lines = lines
+		.into_iter()
+		.map(|s| s.strip_prefix(' ').unwrap_or(s))
+		.collect::<Vec<_>>();
+	if lines.is_empty() {
+		None
+	} else {
+		Some(lines.join("\n"))
+	}
+}
@@ -26,32 +26,49 @@ pub fn parse_regex(input: &str) -> IResult<&str, String> {
 
 	delimited(
 		tag("/"),
-		context(
+		cut(context(
 			"empty regex",
 			fold_many1(parse_fragment, String::new, |buf, s| buf + s),
-		),
-		tag("/"),
+		)),
+		cut(context("unterminated regex", tag("/"))),
 	)(input)
 }
 
 pub fn parse_filter(input: &str) -> IResult<&str, Filter<'_>> {
 	// The syntax for filters is exactly like a function call in rust.
-	// Arguments  are comma separated quoted strings.
+	// Arguments  are comma separated quoted strings or regex literals.
 	// A string literal can be used as a shorthand for `eq("...")`.
-	let args = wrap_space0(list0(parse_string, ','));
-	let args = delimited(char('('), args, char(')'));
-
-	let normal = map(pair(parse_keyword, args), |(name, args)| Filter {
-		name,
+	let arg = alt((
+		map(parse_string, |s| (s, false)),
+		map(parse_regex, |s| (s, true)),
+	));
+	let args = wrap_space0(list0(arg, ','));
+	let args = delimited(
+		char('('),
 		args,
-	});
-	let short = map(parse_string, |s| Filter {
+		cut(context("missing closing delimiter: ')'", char(')'))),
+	);
+
+	let normal = map(
+		consumed(pair(parse_keyword, args)),
+		|(src, (name, args)): (_, (_, Vec<_>))| Filter {
+			name,
+			regex_args: args.iter().any(|(_, is_regex)| *is_regex),
+			args: args.into_iter().map(|(s, _)| s).collect(),
+			src,
+		},
+	);
+	let short = map(consumed(parse_string), |(src, s)| Filter {
 		name: "eq",
 		args: vec![s],
+		regex_args: false,
+		src,
 	});
-	let reg = map(parse_regex, |s| Filter {
+	let reg = map(consumed(parse_regex), |(src, s)| Filter {
 		name: "regex",
 		args: vec![s],
+		regex_args: true,
+		src,
 	});
 
 	alt((normal, short, reg))(input)
@@ -17,16 +17,18 @@ fn parse_esc_char(input: &str) -> IResult<&str, char> {
 		char('\\'),
 		// `alt` tries each parser in sequence, returning the result of
 		// the first successful match
-		alt((
+		cut(context("invalid escape sequence", alt((
 			// The `value` parser returns a fixed value (the first argument) if its
 			// parser (the second argument) succeeds.
 			value('\\', char('\\')),
 			value('\n', char('n')),
 			value('<', char('<')),
 			value('[', char('[')),
+			value('{', char('{')),
+			value('/', char('/')),
 			value('\r', char('r')),
 			value('\t', char('t')),
-		)),
+		)))),
 	)(input)
 }
 
@@ -11,7 +11,6 @@ pub use nom::{
 		tag_no_case,
 		take,
 		take_till,
-		take_until,
 		take_while,
 	},
 	character::complete::{
@@ -22,6 +21,7 @@ pub use nom::{
 		space1,
 	},
 	combinator::{
+		consumed,
 		cut,
 		map,
 		opt,
@@ -30,10 +30,7 @@ pub use nom::{
 		value,
 		verify,
 	},
-	error::{
-		context,
-		ParseError,
-	},
+	error::context,
 	multi::{
 		fold_many0,
 		fold_many1,
@@ -46,6 +43,7 @@ pub use nom::{
 		pair,
 		preceded,
 		separated_pair,
+		terminated,
 	},
 };
 
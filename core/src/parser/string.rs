@@ -66,5 +66,9 @@ fn string_parser<'a>(quo: char) -> impl FnMut(&'a str) -> IResult<&'a str, Strin
 		buf
 	});
 
-	delimited(char(quo), build_string, char(quo))
+	delimited(
+		char(quo),
+		build_string,
+		cut(context("unterminated string", char(quo))),
+	)
 }
@@ -16,7 +16,7 @@ macro_rules! pretty_eq {
 	};
 }
 
-fn lit(s: &str) -> Segment {
+fn lit(s: &str) -> Segment<'_> {
 	Segment::Text(String::from(s))
 }
 
@@ -33,6 +33,8 @@ macro_rules! filter {
 		$crate::parser::Filter{
 			name: $name,
 			args: Vec::new(),
+			regex_args: false,
+			src: "",
 		}
 	};
 	[$name:literal, $($x:expr),+ $(,) ?] => {{
@@ -42,6 +44,8 @@ macro_rules! filter {
 			$crate::parser::Filter{
 				name: $name,
 				args,
+				regex_args: false,
+				src: "",
 		}
 	}};
 }
@@ -65,6 +69,7 @@ macro_rules! capture {
 			name,
 			quantifier,
 			patterns: vec![],
+			description: None,
 		}
 	}};
 	// Only 1 pattern, arguments are filters
@@ -73,9 +78,11 @@ macro_rules! capture {
 		Capture {
 			name,
 			quantifier,
-			patterns: vec![Pattern(vec![
-			$($filter),*
-			])],
+			patterns: vec![Pattern {
+				filters: vec![$($filter),*],
+				src: "",
+			}],
+			description: None,
 		}
 	}};
 	// Multiple patterns, arguments are patterns
@@ -85,13 +92,17 @@ macro_rules! capture {
 			name,
 			quantifier,
 			patterns: vec![$($pattern),*],
+			description: None,
 		}
 	}};
 }
 
 macro_rules! pattern {
 	[$($filter:expr),* $(;)?] => {
-		Pattern(vec![$($filter),* ])
+		Pattern {
+			filters: vec![$($filter),*],
+			src: "",
+		}
 	};
 }
 
@@ -161,6 +172,10 @@ fn test_filter() {
 			"super-duper-1('1',\t'2' , \n'3')",
 			filter!("super-duper-1", "1", "2", "3"),
 		),
+		(
+			r"extract(/\d+ \d+/, 'a')",
+			filter!("extract", r"\d+ \d+", "a"),
+		),
 	];
 
 	for (s, expected) in tests {
@@ -264,7 +279,7 @@ fn test_command() {
 	];
 
 	for (s, expected) in tests {
-		let got = check!(parse_command, s);
+		let (got, _) = check!(parse_command, s);
 		if expected.len() != got.len() {
 			panic!(
 				"different lengths: expected {}, got {}",
@@ -272,7 +287,7 @@ fn test_command() {
 				got.len()
 			);
 		}
-		for (left, right) in expected.iter().zip(got.iter()) {
+		for (left, (_, right)) in expected.iter().zip(got.iter()) {
 			pretty_eq!(left, right);
 		}
 	}
@@ -288,3 +303,43 @@ fn test_regex() {
 		assert_eq!(expected, got);
 	}
 }
+
+#[test]
+fn test_comments() {
+	let s = "//! Plays a song.
+//! Second line.
+?play // a comment
+//// not a doc comment
+/// The song.
+<song>
+{
+	// Not documented.
+	<a>
+	///Documented.
+	<b>
+}
+//!
+//!   Indented.
+";
+	let (got, description) = check!(parse_command, s);
+	assert_eq!(
+		Some("Plays a song.\nSecond line.\n\n  Indented."),
+		description.as_deref()
+	);
+
+	let mut song = capture!("song");
+	song.description = Some(String::from("The song."));
+	let a = capture!("a");
+	let mut b = capture!("b");
+	b.description = Some(String::from("Documented."));
+	let expected = vec![lit("?play"), Segment::Capture(song), Segment::Group(vec![a, b])];
+
+	let got = got.into_iter().map(|(_, seg)| seg).collect::<Vec<_>>();
+	assert_eq!(expected, got);
+
+	let (got, _) = check!(parse_command, "//roll <n>");
+	assert_eq!(vec![lit("//roll"), Segment::Capture(capture!("n"))], got.into_iter().map(|(_, seg)| seg).collect::<Vec<_>>());
+
+	let (got, _) = check!(parse_command, r"\//foo a//b");
+	assert_eq!(vec![lit("//foo"), lit("a//b")], got.into_iter().map(|(_, seg)| seg).collect::<Vec<_>>());
+}
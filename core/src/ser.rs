@@ -0,0 +1,115 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2024 Taylan Gökkaya
+
+// This file is licensed under the terms of Apache-2.0 License.
+
+//! Implementations for the `serde` feature.
+
+use serde::{
+	de::Error as _,
+	Deserialize,
+	Deserializer,
+	Serialize,
+	Serializer,
+};
+
+use crate::{
+	ast::Segment,
+	Command,
+	Limit,
+	Overflow,
+};
+
+/// Serializes a `Vec<Regex>` as the sources of the regexes.
+pub mod regexes {
+	use regex::Regex;
+
+	use super::*;
+
+	pub fn serialize<S: Serializer>(reg: &[Regex], s: S) -> Result<S::Ok, S::Error> {
+		s.collect_seq(reg.iter().map(Regex::as_str))
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<Regex>, D::Error> {
+		Vec::<String>::deserialize(d)?
+			.iter()
+			.map(|s| Regex::new(s).map_err(D::Error::custom))
+			.collect()
+	}
+}
+
+/// Like [regexes] but for the regexes of the `extract` filter, which are
+/// serialized without their anchor.
+pub mod anchored_regexes {
+	use regex::Regex;
+
+	use super::*;
+	use crate::ast::{
+		anchor,
+		unanchor,
+	};
+
+	pub fn serialize<S: Serializer>(reg: &[Regex], s: S) -> Result<S::Ok, S::Error> {
+		s.collect_seq(reg.iter().map(unanchor))
+	}
+
+	pub fn deserialize<'de, D: Deserializer<'de>>(d: D) -> Result<Vec<Regex>, D::Error> {
+		Vec::<String>::deserialize(d)?
+			.iter()
+			.map(|s| anchor(s).map_err(D::Error::custom))
+			.collect()
+	}
+}
+
+#[derive(Serialize)]
+struct CommandRef<'a> {
+	#[serde(skip_serializing_if = "Option::is_none")]
+	description: Option<&'a str>,
+	segments: &'a [Segment],
+}
+
+#[derive(Deserialize)]
+struct CommandRepr {
+	#[serde(default)]
+	description: Option<String>,
+	segments: Vec<Segment>,
+}
+
+impl Serialize for Command {
+	fn serialize<S: Serializer>(&self, s: S) -> Result<S::Ok, S::Error> {
+		CommandRef {
+			description: self.description.as_deref(),
+			segments: &self.segments,
+		}
+		.serialize(s)
+	}
+}
+
+/// A [Limit] before its `max` is checked.
+#[derive(Deserialize)]
+pub(crate) struct LimitRepr {
+	max: usize,
+	overflow: Overflow,
+}
+
+impl TryFrom<LimitRepr> for Limit {
+	type Error = &'static str;
+
+	fn try_from(l: LimitRepr) -> Result<Self, Self::Error> {
+		if l.max == 0 {
+			return Err("the maximum of a limit must be at least 1");
+		}
+		Ok(match l.overflow {
+			Overflow::Truncate => Self::truncate(l.max),
+			Overflow::Fail => Self::fail(l.max),
+		})
+	}
+}
+
+/// The same rules as [Command::new] are checked after deserializing.
+impl<'de> Deserialize<'de> for Command {
+	fn deserialize<D: Deserializer<'de>>(d: D) -> Result<Self, D::Error> {
+		let c = CommandRepr::deserialize(d)?;
+		Command::from_segments(c.segments, c.description).map_err(D::Error::custom)
+	}
+}
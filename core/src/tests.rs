@@ -0,0 +1,920 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2024 Taylan Gökkaya
+
+// This file is licensed under the terms of Apache-2.0 License.
+
+use crate::*;
+
+macro_rules! check {
+	[$x:expr] => {
+		$x.unwrap_or_else(|e| {
+			panic!("error: {}", e);
+		})
+	};
+}
+
+impl<'a> From<&'a str> for Match<'a> {
+	fn from(s: &'a str) -> Self {
+		Self::Once(s)
+	}
+}
+
+impl<'a> From<Vec<&'a str>> for Match<'a> {
+	fn from(v: Vec<&'a str>) -> Self {
+		Self::Many(v)
+	}
+}
+
+macro_rules! map {
+	[$($key:literal : $val:expr),* $(,)?] => {{
+		let mut map = std::collections::HashMap::new();
+		$(
+		map.insert($key, $val);
+		)*
+		map
+	}};
+}
+
+macro_rules! vals {
+	[$($key:literal : $val:expr),* $(,)?] => {{
+		use std::collections::HashMap;
+		let mut map: HashMap<&str, Match<'_>> = HashMap::new();
+		$(
+		map.insert($key, $val.into());
+		)*
+		map
+	}};
+}
+
+/// Returns `cmd` along with the commands compiled from its `Display` and
+/// pretty `Display` output.
+fn reparse(cmd: &Command) -> [Command; 3] {
+	let s = cmd.to_string();
+	let pretty = format!("{:#}", cmd);
+	let compile = |s: &str| {
+		Command::new(s).unwrap_or_else(|e| panic!("failed to compile the output of Display: {e}\n{s}"))
+	};
+	[cmd.clone(), compile(&s), compile(&pretty)]
+}
+
+#[test]
+fn match_succeed() {
+	let tests = vec![
+		(
+			".bet <amount>",
+			map! {
+				".bet 42": vals! {
+					"amount": "42",
+				},
+				".bet -42\t": vals! {
+					"amount": "-42",
+					"rest": "\t",
+				},
+				".bet\nasdf\nnice": vals!{
+					"amount": "asdf",
+					"rest": "\nnice",
+				},
+			},
+		),
+		(
+			r".run
+<flags*: starts(`--`)>
+<code:
+	starts('```'), ends('```');
+	starts('`'), ends('`');
+>",
+			map! {
+				".run --debug `banana`": vals! {
+					"flags": vec!["debug"],
+					"code": "banana",
+				},
+				".run --1 --2 --3 ```\nmultiline\n```\ntrailing": vals! {
+					"flags": vec!["1", "2", "3"],
+					"code": "\nmultiline\n",
+					"rest": "\ntrailing",
+				},
+				".run `bar`": vals!{
+					"code": "bar",
+				},
+			},
+		),
+		(
+			"?note [
+	<oldest?: `!oldest`, nocase()>
+	<tags*: starts(`-`, `+`)>
+	<name>]",
+			map! {
+				"?note -tag1 -tag2 banana": vals!{
+					"tags": vec!["tag1", "tag2"],
+					"name": "banana",
+					"rest": "",
+				},
+				"?note -tag1 !OldesT banana -tag2 this trails": vals!{
+					"name": "banana",
+					"oldest": "!OldesT",
+					"tags": vec!["tag1", "tag2"],
+					"rest": " this trails",
+				},
+			},
+		),
+		(
+			"!foo [<flags+: starts(`-`), notrim()> <_*>]",
+			map! {
+				"!foo -a -b -c d -e": vals! {
+					"flags": vec!["-a", "-b", "-c", "-e"],
+					"_": vec!["d"],
+					"rest": "",
+				},
+			},
+		),
+		(
+			"!foo <quoted+: notrim(), starts(`'`), ends(`'`); notrim(), starts('`'), ends('`')>",
+			map! {
+				"!foo `it's nice` 'isn`t it?'": vals! {
+					"quoted": vec!["`it's nice`", "'isn`t it?'"],
+					"rest": "",
+				},
+				"!foo `a b c d e ` ` 1 2 3 4 5 `": vals! {
+					"quoted": vec!["`a b c d e `", "` 1 2 3 4 5 `"],
+					"rest": "",
+				},
+			},
+		),
+		(
+			r"!add <n1: /^\-?\d+$/> <nums+: /^\-?\d+$/>",
+			map! {
+				"!add 2 42": vals!{
+					"n1": "2",
+					"nums": vec!["42"],
+					"rest": "",
+				},
+				"!add -42 42 -42 0": vals! {
+					"n1": "-42",
+					"nums": vec!["42", "-42", "0"],
+					"rest": "",
+				},
+			},
+		),
+		(
+			"!foo [<n: /^[0-9]+$/>]",
+			map! {
+				"!foo 42": vals!{"n": "42", "rest": ""},
+			},
+		),
+		(
+			"$foo <amount: /^[0-9]+$/>",
+			map! {
+				"$foo 0": vals!{"amount": "0", "rest": ""},
+			},
+		),
+		(
+			r#"?calc <expr: starts("("), ends(")"), balanced()>"#,
+			map! {
+				"?calc ((1+2)*3) = 9": vals!{"expr": "(1+2)*3", "rest": " = 9"},
+				"?calc (1) (2)": vals!{"expr": "1", "rest": " (2)"},
+			},
+		),
+		(
+			r#"?json <obj: starts("{"), ends("}"), balanced(`"`), notrim()>"#,
+			map! {
+				r#"?json {"a": {"b": "}"}} trailing"#: vals!{
+					"obj": r#"{"a": {"b": "}"}}"#,
+					"rest": " trailing",
+				},
+			},
+		),
+		(
+			r#"?say <msg: starts('"'), ends('"'), escape("\\")> <to?>"#,
+			map! {
+				r#"?say "say \"hi\"" bob"#: vals!{"msg": r#"say \"hi\""#, "to": "bob"},
+				r#"?say "c:\\" bob"#: vals!{"msg": r"c:\\", "to": "bob"},
+			},
+		),
+		(
+			r#"?path <path: ends(";"), escape("\\")>"#,
+			map! {
+				r"?path a\;b;c": vals!{"path": r"a\;b", "rest": ";c"},
+			},
+		),
+		(
+			"?eval <code: codeblock()>",
+			map! {
+				"?eval ```rust\nfn main() {}\n``` trailing": vals!{
+					"code": "fn main() {}\n",
+					"rest": " trailing",
+				},
+				"?eval ```no language```": vals!{"code": "no language"},
+				"?eval ```\ncode```": vals!{"code": "code"},
+				"?eval ```\r\nfn main() {}\n```": vals!{"code": "fn main() {}\n"},
+				"?eval `inline`": vals!{"code": "inline"},
+				"?eval ``in`line``": vals!{"code": "in`line"},
+			},
+		),
+		(
+			r"?remind <date: extract(/\d{4}-\d{2}-\d{2} \d{2}:\d{2}/, /tomorrow/)> <msg+>",
+			map! {
+				"?remind 2024-01-02 13:37 call mom": vals!{
+					"date": "2024-01-02 13:37",
+					"msg": vec!["call", "mom"],
+				},
+				"?remind tomorrow wake up": vals!{
+					"date": "tomorrow",
+					"msg": vec!["wake", "up"],
+				},
+			},
+		),
+	];
+
+	for (src, map) in tests {
+		let cmd = check!(Command::new(src));
+
+		for cmd in reparse(&cmd) {
+			for (input, expected) in &map {
+				let got = cmd.get_matches(input).unwrap_or_else(|| {
+					panic!("returned none:\n{}\ncommand: {}", input, cmd);
+				});
+
+				assert_eq!(
+					expected.get("rest").or(Some(&Match::Once(""))),
+					Some(&Match::Once(got.rest)),
+					"trailing match is not equal",
+				);
+				for (key, val) in expected {
+					if *key == "rest" {
+						continue;
+					}
+					let got = got.get(key);
+
+					assert_eq!(Some(val), got, "\ncommand: {cmd}");
+				}
+			}
+		}
+	}
+}
+
+#[test]
+fn match_fail() {
+	let tests = map! {
+		r"!add <n1: /^\-?\d+$/> <nums+: /^\-?\d+$/>": vec!["!add haha 0", "!add 24 0_0", "!add - 2 2"],
+		"?foo <_>": vec!["?foo", "asdf asdf"],
+		r#"?calc <expr: starts("("), ends(")"), balanced()>"#: vec!["?calc ((1+2)*3"],
+		r"?remind <date: extract(/\d{4}-\d{2}-\d{2}/)>": vec!["?remind x 2024-01-02", "?remind 2024-01"],
+		r#"?eval <code: codeblock("rust", "rs")>"#: vec!["?eval `inline`", "?eval ```py\nprint()```", "?eval ```rust\n```"],
+	};
+
+	for (src, cases) in tests {
+		let cmd = check!(Command::new(src));
+		for cmd in reparse(&cmd) {
+			for s in &cases {
+				let m = cmd.get_matches(s);
+				assert_eq!(None, m, "\ncommand: {cmd}");
+			}
+		}
+	}
+}
+
+#[test]
+fn match_reparsed() {
+	// A lone modifier is warned about in the source but not in the output.
+	let cmd = check!(Command::new("<a: nocase()>"));
+	assert_eq!(1, cmd.lint().len());
+	let [_, again, pretty] = reparse(&cmd);
+	assert_eq!(Vec::<Lint>::new(), again.lint(), "\ncommand: {again}");
+	assert_eq!(Vec::<Lint>::new(), pretty.lint(), "\ncommand: {pretty}");
+
+	// Limits are not part of the source.
+	let opts = CompileOptions::new().limit(Limit::fail(2));
+	let cmd = check!(Command::with_options("?x <a*>", &opts));
+	assert!(cmd.get_matches("?x 1 2 3").is_none());
+	let again = check!(Command::new(&cmd.to_string()));
+	assert!(again.get_matches("?x 1 2 3").is_some());
+	let again = check!(Command::with_options(&cmd.to_string(), &opts));
+	assert!(again.get_matches("?x 1 2 3").is_none());
+}
+
+#[test]
+fn match_groups() {
+	let cmd = check!(Command::new(
+		r#"?eval <code+: codeblock("rust", "py"), nocase()>"#
+	));
+	let args = cmd
+		.get_matches("?eval ```Rust\nlet x = 1;``` ```py\nx = 1```")
+		.unwrap();
+
+	assert_eq!(Some(&vec!["let x = 1;", "x = 1"]), args.get_many("code"));
+	assert_eq!(Some("Rust"), args.group("code", "lang"));
+	let groups = args.groups("code").unwrap();
+	assert_eq!(2, groups.len());
+	assert_eq!(Some("py"), groups[1].get("lang"));
+	assert_eq!(Some("```py\nx = 1```"), groups[1].get("0"));
+
+	let cmd = check!(Command::new(r"?eval <code: codeblock(), /^let (\w+)/>"));
+	let args = cmd.get_matches("?eval ```rs\nlet x = 1;```").unwrap();
+	assert_eq!(Some("x"), args.group("code", "1"));
+	assert_eq!(Some("rs"), args.group("code", "lang"));
+	assert_eq!(None, args.group("code", "2"));
+}
+
+#[test]
+fn match_regex_groups() {
+	let cmd = check!(Command::new(
+		r"?resize <size: /^(?P<w>\d+)x(?P<h>\d+)$/; /^(\d+)%$/>"
+	));
+
+	let args = cmd.get_matches("?resize 800x600").unwrap();
+	assert_eq!(Some("800x600"), args.get_once("size"));
+	assert_eq!(Some("800"), args.group("size", "w"));
+	assert_eq!(Some("600"), args.group("size", "2"));
+	assert_eq!(None, args.group("size", "3"));
+
+	let args = cmd.get_matches("?resize 50%").unwrap();
+	assert_eq!(Some("50"), args.group("size", "1"));
+	assert_eq!(None, args.group("size", "w"));
+
+	let cmd = check!(Command::new(r"?at <t: extract(/(?P<h>\d+):(?P<m>\d+) ?(am|pm)?/)>"));
+	let args = cmd.get_matches("?at 10:30 pm").unwrap();
+	assert_eq!(Some("10:30 pm"), args.get_once("t"));
+	assert_eq!(Some("30"), args.group("t", "m"));
+	assert_eq!(Some("pm"), args.group("t", "3"));
+
+	// Regexes without groups produce no groups.
+	let cmd = check!(Command::new(r"?add <n+: /^\d+$/>"));
+	let args = cmd.get_matches("?add 1 2").unwrap();
+	assert_eq!(None, args.groups("n"));
+}
+
+#[test]
+fn lint() {
+	fn opt(first: &str, second: &str) -> Warning {
+		Warning::OptionalCaptures {
+			first: first.into(),
+			second: second.into(),
+		}
+	}
+	fn eq(capture: &str, shadowed: &str, by: &str) -> Warning {
+		Warning::ShadowedEq {
+			capture: capture.into(),
+			shadowed: shadowed.into(),
+			by: by.into(),
+		}
+	}
+	fn filter(capture: &str, filter: Modifier) -> Warning {
+		Warning::IneffectiveFilter {
+			capture: capture.into(),
+			filter,
+		}
+	}
+
+	let tests = vec![
+		("?foo <a> <b: starts('-')>", vec![]),
+		("?foo {<a?> <b: eq('x')> <c*>}", vec![opt("a", "c")]),
+		("?foo [<a?> <b?>]", vec![]),
+		("?foo <a: eq('p', 'play')>", vec![eq("a", "play", "p")]),
+		("?foo <a: eq('play'); nocase(), eq('P')>", vec![]),
+		("?foo <a: nocase(), eq('P'); eq('pl')>", vec![eq("a", "pl", "P")]),
+		("?foo <a: notrim()> <b: nocase(), /x/>", vec![
+			filter("a", Modifier::NoTrim),
+			filter("b", Modifier::NoCase),
+		]),
+		("?foo <a: nocase(), notrim(), starts('x')>", vec![]),
+	];
+
+	for (s, expected) in tests {
+		let cmd = check!(Command::new(s));
+		let got = cmd.lint().into_iter().map(|l| l.warning).collect::<Vec<_>>();
+		assert_eq!(expected, got, "{s}");
+	}
+
+	let lints = check!(Command::new("?foo\n<a: eq('x', 'xy')>")).lint();
+	assert_eq!(Some(2), lints[0].location.as_ref().map(|l| l.line_no));
+}
+
+#[test]
+fn ambiguities() {
+	let tests: Vec<(&str, Vec<(&str, &str)>)> = vec![
+		("?foo <a+: starts('-')> <b+: starts('--')>", vec![("a", "b")]),
+		("?foo <a: starts('-')> <b+: starts('--')>", vec![]),
+		("?foo <a*: starts('-')> <b: starts('+')>", vec![]),
+		("?foo <a?: eq('x')> <b: nocase(), eq('X', 'y')>", vec![("a", "b")]),
+		(r"?foo <a*: /^\d+$/> <b: eq('42')>", vec![("a", "b")]),
+		(r"?foo <a*: /^\d+$/> <b: eq('x')>", vec![]),
+		("?foo <a*> <b: eq('x')>", vec![("a", "b")]),
+		// Captures with patterns are tried first in normal groups.
+		("?foo {<a?> <b: starts('-')>}", vec![("b", "a")]),
+		("?foo [<a?> <b: starts('-')>]", vec![("a", "b")]),
+		("?foo {<a: eq('x')> <b: eq('y')>}", vec![]),
+		("?foo <a*: codeblock()> <b: codeblock('rs')>", vec![("a", "b")]),
+		// Regexes overlap unless their anchored literals differ.
+		(r"?foo <a*: /^\d+$/> <b: /^[a-z]+$/>", vec![("a", "b")]),
+		(r"?foo <a*: /^x\d/> <b: /^y\d/>", vec![]),
+		(r"?foo <a*: /\.png$/> <b: /\.jpg$/>", vec![]),
+		(r"?foo <a*: /(?i)\.png$/> <b: /\.PNG$/>", vec![("a", "b")]),
+		(r"?foo <a*: /^a|b/> <b: /^c/>", vec![("a", "b")]),
+		(r"?foo <a*: extract(/\d+ \d+/)> <b: extract(/[a-z]+ \d/)>", vec![("a", "b")]),
+		(r"?foo <a*: extract(/^x \d/)> <b: extract(/^y \d/)>", vec![]),
+		// Regexes and delimiters.
+		(r"?foo <a*: /^\d+$/> <b: starts('#')>", vec![("a", "b")]),
+		(r"?foo <a*: /^x/> <b: starts('#')>", vec![]),
+		(r"?foo <a*: starts('#'), /\.rs$/> <b: /\.md$/>", vec![]),
+		(r"?foo <a*: /^x/> <b: ends(';')>", vec![("a", "b")]),
+		("?foo <a*: starts('('), ends(')')> <b: starts('['), ends(']')>", vec![]),
+		("?foo <a*: starts('('), ends(')')> <b: ends(';')>", vec![("a", "b")]),
+		// Extract, words and delimiters.
+		(r"?foo <a*: extract(/\d+ \d+/)> <b: /^\d+$/>", vec![("a", "b")]),
+		(r"?foo <a*: extract(/^# \d+/)> <b: starts('#')>", vec![("a", "b")]),
+		(r"?foo <a*: extract(/^x \d+/)> <b: starts('#')>", vec![]),
+		(r"?foo <a*: extract(/^`x/)> <b: codeblock()>", vec![("a", "b")]),
+	];
+
+	for (s, expected) in tests {
+		let cmd = check!(Command::new(s));
+		let report = cmd.ambiguities();
+		let got = report
+			.iter()
+			.map(|a| (a.winner.as_str(), a.loser.as_str()))
+			.collect::<Vec<_>>();
+		assert_eq!(expected, got, "{s}: {report}");
+	}
+}
+
+#[test]
+fn builder() {
+	use builder::{
+		Capture,
+		Pattern,
+	};
+
+	let quoted = Pattern::new().starts(["\""]).ends(["\""]).escape(["\\"]);
+	let cmd = check!(Command::builder()
+		.text("?tag <x>")
+		.capture(Capture::new("name").pattern(quoted))
+		.group([
+			Capture::new("flag")
+				.quantifier(ast::Quantifier::MaybeOnce)
+				.pattern(Pattern::new().nocase().eq(["-f", "--force"])),
+			Capture::new("n").pattern(Pattern::new().regex(r"^\d+$")),
+		])
+		.capture(
+			Capture::new("code")
+				.quantifier(ast::Quantifier::Many0)
+				.pattern(Pattern::new().codeblock(["rs"])),
+		)
+		.build());
+	let src = check!(Command::new(
+		r#"?tag\ \<x> <name: starts('"'), ends('"'), escape("\\")> {<flag?: nocase(), eq("-f", "--force")> <n: /^\d+$/>} <code*: codeblock("rs")>"#
+	));
+	assert_eq!(src.to_string(), cmd.to_string());
+
+	let args = cmd.get_matches(r#"?tag <x> "a \" b" 5 -F"#).unwrap();
+	assert_eq!(Some(r#"a \" b"#), args.get_once("name"));
+	assert_eq!(Some("-F"), args.get_once("flag"));
+
+	// The same validation as `Command::new` runs.
+	let res = Command::builder()
+		.capture(Capture::new("a").quantifier(ast::Quantifier::Many0))
+		.capture(Capture::new("b").quantifier(ast::Quantifier::Many1))
+		.build();
+	assert!(matches!(
+		res,
+		Err(Error::Rule(RuleError::NonDeterministicSequence, _))
+	));
+
+	let res = Command::builder()
+		.capture(Capture::new("a").pattern(Pattern::new().regex("(")))
+		.build();
+	assert!(matches!(
+		res,
+		Err(Error::Filter(FilterError::Regex(_), _))
+	));
+
+	let res = Command::builder()
+		.capture(Capture::new("a").pattern(Pattern::new().eq(["x"]).starts(["y"])))
+		.build();
+	assert!(matches!(res, Err(Error::Filter(FilterError::Eq, _))));
+
+	let res = Command::builder()
+		.capture(Capture::new("a").pattern(Pattern::new()))
+		.build();
+	assert!(matches!(res, Err(Error::Filter(FilterError::NoFilters, _))));
+
+	for name in ["a b", "a>", "<a"] {
+		let res = Command::builder().capture(Capture::new(name)).build();
+		assert!(
+			matches!(&res, Err(Error::Rule(RuleError::InvalidName(n), _)) if n == name),
+			"{name:?}: {res:?}"
+		);
+	}
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn serde() {
+	let src = r#"//! Foo.
+?foo <a?: nocase(), eq("x")> {/// B.
+<b*: starts("-"), /^-\w+$/> <c: extract(/\d+ \d+/)>} [<d: codeblock("rs")>]"#;
+	let cmd = check!(Command::new(src));
+	let json = check!(serde_json::to_string(&cmd));
+	let de: Command = check!(serde_json::from_str(&json));
+	assert_eq!(cmd.to_string(), de.to_string());
+
+	let input = "?foo X 1 2 -ab `x`";
+	assert_eq!(cmd.get_matches(input), de.get_matches(input));
+
+	// Rules are checked after deserializing.
+	let json = r#"{"segments": [
+		{"capture": {"name": "a", "quantifier": "many0", "patterns": []}},
+		{"capture": {"name": "b", "quantifier": "many1", "patterns": []}}
+	]}"#;
+	assert!(serde_json::from_str::<Command>(json).is_err());
+	let json = r#"{"segments": [
+		{"capture": {"name": "a", "quantifier": "once", "patterns": [{"word": {"reg": ["("]}}]}}
+	]}"#;
+	assert!(serde_json::from_str::<Command>(json).is_err());
+
+	// So are the checks done on filters and capture names.
+	let pattern = |p: serde_json::Value| {
+		serde_json::json!({"segments": [
+			{"capture": {"name": "a", "quantifier": "once", "patterns": [p]}}
+		]})
+	};
+	let delimited = |starts: &[&str], ends: &[&str], balanced: bool, escapes: &[&str]| {
+		pattern(serde_json::json!({"delimited": {
+			"starts": starts, "ends": ends, "reg": [], "no_case": false, "no_trim": false,
+			"balanced": balanced, "quotes": [], "escapes": escapes,
+		}}))
+	};
+	let invalid = [
+		pattern(serde_json::json!({"eq": {"any_of": [], "no_case": false}})),
+		pattern(serde_json::json!({"extract": {"reg": []}})),
+		delimited(&[], &[], false, &[]),
+		delimited(&["("], &[], true, &[]),
+		delimited(&["("], &[], false, &["\\"]),
+		serde_json::json!({"segments": [{"capture": {"name": "a b", "quantifier": "once", "patterns": []}}]}),
+	];
+	for json in invalid {
+		assert!(serde_json::from_value::<Command>(json.clone()).is_err(), "{json}");
+	}
+	assert!(serde_json::from_value::<Command>(delimited(&["("], &[")"], true, &["\\"])).is_ok());
+
+	let limit: Limit = check!(serde_json::from_str(r#"{"max": 3, "overflow": "fail"}"#));
+	assert_eq!(Limit::fail(3), limit);
+	assert!(serde_json::from_str::<Limit>(r#"{"max": 0, "overflow": "fail"}"#).is_err());
+
+	let cmd = check!(Command::new("?foo <a: /^(?<n>\\d+)$/> <b*>"));
+	let args = cmd.get_matches("?foo 42 x y").unwrap();
+	let json = check!(serde_json::to_value(&args));
+	assert_eq!(
+		serde_json::json!({
+			"rest": "",
+			"captures": {"a": "42", "b": ["x", "y"]},
+			"groups": {"a": [[[null, "42"], ["n", "42"]]]},
+		}),
+		json
+	);
+}
+
+#[test]
+fn usage() {
+	let tests = &[
+		("?ban <user> <reason*>", "?ban <user> [<reason>...]", "`?ban` *user* [*reason*...]"),
+		(
+			".sort [<descending?: nocase(), `-desc`, `-reverse`> <words+>]",
+			".sort [-desc|-reverse] <words>...",
+			"`.sort` [`-desc`|`-reverse`] *words*...",
+		),
+		(
+			r#"?say <msg: starts('"'), ends('"'); starts("'"), ends("'")> <n?: /^\d+$/>"#,
+			r#"?say "<msg>"|'<msg>' [<n>]"#,
+			r#"`?say` `"`*msg*`"`|`'`*msg*`'` [*n*]"#,
+		),
+		(
+			"?eval <code: codeblock()>",
+			"?eval ```<code>```",
+			"`?eval` `` ``` ``*code*`` ``` ``",
+		),
+	];
+
+	for (src, plain, markdown) in tests {
+		let cmd = check!(Command::new(src));
+		assert_eq!(*plain, cmd.usage(), "{src}");
+		assert_eq!(*markdown, cmd.usage_with(UsageStyle::Markdown), "{src}");
+	}
+}
+
+#[test]
+fn introspection() {
+	let cmd = check!(Command::new(
+		"?tag add [<mode?: eq('a', 'b')> <color?: starts('c=', 'color=')>] <name> <args*>"
+	));
+
+	assert_eq!(vec!["?tag", "add"], cmd.literal_prefix());
+	let names: Vec<_> = cmd.captures().map(|c| c.name.as_str()).collect();
+	assert_eq!(vec!["mode", "color", "name", "args"], names);
+	let required: Vec<_> = cmd.required_captures().map(|c| c.name.as_str()).collect();
+	assert_eq!(vec!["name"], required);
+
+	assert_eq!(Some(vec!["a", "b"]), cmd.choices("mode"));
+	assert_eq!(None, cmd.choices("color"));
+	assert_eq!(None, cmd.choices("name"));
+	assert_eq!(None, cmd.choices("missing"));
+
+	let color = cmd.capture("color").unwrap();
+	assert_eq!(vec!["c=", "color="], color.prefixes());
+	assert!(!color.is_required());
+	assert!(cmd.capture("args").unwrap().is_many());
+
+	let cmd = check!(Command::new("<a> foo"));
+	assert!(cmd.literal_prefix().is_empty());
+}
+
+#[test]
+fn parse_args() {
+	let cmd = check!(Command::new("?roll <n> <sides?: starts('d')> <mods*: starts('+')>"));
+	let args = cmd.get_matches("?roll 2 d20 +1 +3").unwrap();
+	assert_eq!(Some(2_u32), check!(args.parse_once("n")));
+	assert_eq!(Some(20_u32), check!(args.parse_once("sides")));
+	assert_eq!(vec![1_i32, 3], check!(args.parse_many::<i32>("mods")));
+	assert_eq!(None, check!(args.parse_once::<u32>("mods")));
+
+	let args = cmd.get_matches("?roll x").unwrap();
+	match args.parse_once::<u32>("n") {
+		Err(FromArgsError::Invalid { capture, value, .. }) => {
+			assert_eq!("n", capture);
+			assert_eq!("x", value);
+		}
+		x => panic!("expected an invalid value error, got {x:?}"),
+	}
+	assert!(check!(args.parse_many::<i32>("mods")).is_empty());
+}
+
+#[test]
+fn spans() {
+	let cmd = check!(Command::new(
+		"?note [<tags*: starts('-')> <title>] <code?: codeblock()>"
+	));
+	let input = "?note  -a hello -bc ```rs\nx```";
+	let args = cmd.get_matches(input).unwrap();
+
+	assert_eq!(Some(10..15), args.span("title"));
+	assert_eq!(Some(&[8..9, 17..19][..]), args.spans("tags"));
+	for name in ["tags", "title", "code"] {
+		let vals = match args.get(name).unwrap() {
+			Match::Once(s) => vec![*s],
+			Match::Many(xs) => xs.clone(),
+		};
+		let spans = args.spans(name).unwrap();
+		assert_eq!(vals.len(), spans.len(), "{name}");
+		for (val, span) in vals.iter().zip(spans) {
+			assert_eq!(*val, &input[span.clone()], "{name}");
+		}
+	}
+
+	let mut args = args;
+	args.take("tags");
+	assert_eq!(None, args.span("tags"));
+}
+
+#[test]
+fn ordered_args() {
+	let cmd = check!(Command::new(
+		"?search [<lang?: starts('lang=')> <tags*: starts('#')> <query>] <page?: /^\\d+$/>"
+	));
+	let input = "?search #b lang=rs #a foo 2";
+	let args = cmd.get_matches(input).unwrap();
+
+	let got: Vec<_> = args.iter().collect();
+	assert_eq!(
+		vec![
+			("tags", "b", 9..10),
+			("lang", "rs", 16..18),
+			("tags", "a", 20..21),
+			("query", "foo", 22..25),
+			("page", "2", 26..27),
+		],
+		got
+	);
+
+	let names: Vec<_> = args.into_matches().into_keys().collect();
+	assert_eq!(vec!["tags", "lang", "query", "page"], names);
+}
+
+#[test]
+fn owned_args() {
+	let cmd = check!(Command::new("?remind <when> <what+>"));
+	let args = {
+		let msg = String::from("?remind 5m take a break");
+		cmd.get_matches(&msg).unwrap().into_owned()
+	};
+
+	let mut args = std::thread::spawn(move || args).join().unwrap();
+	assert_eq!("", args.rest);
+	assert_eq!(Some("5m"), args.get_once("when"));
+	assert_eq!(
+		Some(Match::Many(vec!["take", "a", "break"])),
+		args.get("what").map(OwnedMatch::as_match)
+	);
+	assert_eq!(Some(&[11..15, 16..17, 18..23][..]), args.spans("what"));
+
+	#[cfg(feature = "serde")]
+	{
+		let json = check!(serde_json::to_string(&args));
+		let back: OwnedArgs = check!(serde_json::from_str(&json));
+		assert_eq!(args.get("what"), back.get("what"));
+	}
+
+	assert_eq!(
+		Some(vec!["take".to_string(), "a".into(), "break".into()]),
+		args.take_many("what")
+	);
+	assert!(!args.is_present("what"));
+	assert_eq!(None, args.span("what"));
+
+	let cmd = check!(Command::new(r"?eval {<code: codeblock(), /^let (\w+)/> <v*: eq('-v')>}"));
+	let input = "?eval -v ```rs\nlet x = 1;``` -v";
+	let borrowed = cmd.get_matches(input).unwrap();
+	let args = borrowed.clone().into_owned();
+	assert_eq!(
+		borrowed.iter().collect::<Vec<_>>(),
+		args.iter().collect::<Vec<_>>()
+	);
+	assert_eq!(Some("rs"), args.group("code", "lang"));
+	assert_eq!(Some("x"), args.group("code", "1"));
+	let groups = args.groups("code").unwrap();
+	assert_eq!(borrowed.groups("code").unwrap()[0], groups[0].as_groups());
+	assert_eq!(None, args.groups("v"));
+}
+
+#[test]
+fn unescaped() {
+	let cmd = check!(Command::new(
+		r#"?say <to*: starts('"'), ends('"'), escape("\\", "^"); /^\w+$/> <msg: ends(";"), escape("\\")>"#
+	));
+	let args = cmd
+		.get_matches(r#"?say "a \"b\"" bob "c^^ ö\ö" x\;y\\;z"#)
+		.unwrap();
+	let to = args.unescaped("to").unwrap();
+	assert_eq!(vec![r#"a "b""#, "bob", r"c^ öö"], to);
+	// Only escaped values are copied.
+	assert!(matches!(to[1], std::borrow::Cow::Borrowed(_)));
+	assert_eq!(Some(vec![r"x;y\".into()]), args.unescaped("msg"));
+	assert_eq!(";z", args.rest);
+	assert_eq!(None, args.unescaped("nope"));
+
+	let mut owned = args.clone().into_owned();
+	assert_eq!(args.unescaped("to"), owned.unescaped("to"));
+	owned.take("to");
+	assert_eq!(None, owned.unescaped("to"));
+}
+
+#[test]
+fn diagnose() {
+	// (command, input, segment, expected, position, found)
+	let tests = &[
+		("?ban <user>", "?kick bob", 0, "`?ban`", 0, Some("?kick")),
+		("?ban <user>", "?ban  ", 1, "<user>", 6, None),
+		(
+			"?give <user> <amount: /^\\d+$/>",
+			"?give bob lots",
+			2,
+			"<amount>",
+			10,
+			Some("lots"),
+		),
+		(
+			"?note [<tags*: starts('-')> <title>] <body+>",
+			"?note -a -b",
+			1,
+			"<title>",
+			11,
+			None,
+		),
+		("?cmd [<a>] end", "?cmd x y", 2, "`end`", 7, Some("y")),
+		(
+			"?give <words+: /^[a-z]+$/> <amount: /^\\d+$/>",
+			"?give a b c",
+			2,
+			"<amount>",
+			11,
+			None,
+		),
+		(
+			"?roll {<n: /^\\d+$/> <sides?: starts('d')>}",
+			"?roll d6",
+			1,
+			"<n>",
+			8,
+			None,
+		),
+	];
+
+	for &(src, input, segment, expected, position, found) in tests {
+		let cmd = check!(Command::new(src));
+		let m = cmd
+			.diagnose(input)
+			.unwrap_or_else(|| panic!("{src}: expected a mismatch for {input:?}"));
+		assert_eq!(segment, m.segment, "{src}: {input:?}");
+		assert_eq!(expected, m.expected.to_string(), "{src}: {input:?}");
+		assert_eq!(position, m.position, "{src}: {input:?}");
+		assert_eq!(found, m.found, "{src}: {input:?}");
+	}
+
+	let cmd = check!(Command::new("?ban <user>"));
+	assert!(cmd.diagnose("?ban bob").is_none());
+}
+
+#[test]
+fn partial_match() {
+	let cmd = check!(Command::new(
+		"?remind <user> [<every?: starts('every=')> <at: /^\\d+:\\d+$/>] <what+>"
+	));
+
+	let p = cmd.partial_match("?remind bob 12:00 tea");
+	assert!(p.is_complete());
+	assert_eq!(Some(&vec!["tea"]), p.args.get_many("what"));
+
+	let p = cmd.partial_match("?remind bob every=day");
+	assert!(!p.is_complete());
+	assert_eq!(2, p.unmatched.len());
+	assert_eq!(Some("bob"), p.args.get_once("user"));
+	assert!(!p.args.is_present("every"));
+	assert_eq!(" every=day", p.args.rest);
+	let missing: Vec<_> = p.missing().map(|c| c.name.as_str()).collect();
+	assert_eq!(vec!["at", "what"], missing);
+
+	let p = cmd.partial_match("?kick bob");
+	assert_eq!(4, p.unmatched.len());
+	assert!(p.args.into_matches().is_empty());
+}
+
+#[test]
+fn find() {
+	let cmd = check!(Command::new("\\[\\[ <name> ]]"));
+	assert!(cmd.get_matches("ünï [[ x ]]").is_none());
+
+	let text = "ünï [[ lotus ]] and  [[ mox ]] but not[[ this ]]";
+	let got: Vec<_> = cmd
+		.find_iter(text)
+		.map(|(span, mut args)| (span, args.take("name").and_then(Match::once).unwrap()))
+		.collect();
+	assert_eq!(vec![(6..17, "lotus"), (23..32, "mox")], got);
+
+	let (span, args) = cmd.find(text).unwrap();
+	assert_eq!(6..17, span);
+	assert_eq!("lotus", &text[args.span("name").unwrap()]);
+
+	// Captures are not found next to literal text in the same word.
+	let cmd = check!(Command::new(r"\[\[<name+>]]"));
+	assert!(cmd.find("look [[black lotus]] here").is_none());
+	let cmd = check!(Command::new("<name: starts('[['), ends(']]')>"));
+	let (_, args) = cmd.find("look [[black lotus]] here").unwrap();
+	assert_eq!(Some("black lotus"), args.get_once("name"));
+
+	// Commands that can match nothing still advance.
+	let cmd = check!(Command::new("<a?: eq('x')>"));
+	let spans: Vec<_> = cmd.find_iter("ü x").map(|(span, _)| span).collect();
+	assert_eq!(vec![0..0, 3..4], spans);
+}
+
+#[test]
+fn limits() {
+	let words = |n: usize| (0..n).map(|i| i.to_string()).collect::<Vec<_>>().join(" ");
+	let input = format!("?add {}", words(60));
+
+	// The default truncates at 51 values.
+	let cmd = check!(Command::new("?add <nums+>"));
+	let args = cmd.get_matches(&input).unwrap();
+	assert_eq!(51, args.get_many("nums").unwrap().len());
+	assert_eq!(format!(" {}", (51..60).map(|i| i.to_string()).collect::<Vec<_>>().join(" ")), args.rest);
+
+	let opts = CompileOptions::new().limit(Limit::fail(60));
+	let cmd = check!(Command::with_options("?add <nums+>", &opts));
+	assert_eq!(60, cmd.get_matches(&input).unwrap().get_many("nums").unwrap().len());
+	let cmd = check!(Command::with_options("?add <nums*> end", &opts));
+	assert!(cmd.get_matches(&format!("?add {} end", words(61))).is_none());
+	assert!(cmd.get_matches(&format!("?add {} end", words(60))).is_some());
+
+	// Group states are limited as well.
+	let opts = CompileOptions::new()
+		.capture_limit("tags", Limit::fail(2))
+		.capture_limit("nums", Limit::truncate(2));
+	let cmd = check!(Command::with_options(
+		"?t [<tags*: starts('#')> <nums*: /^\\d+$/>]",
+		&opts
+	));
+	let args = cmd.get_matches("?t 1 #a 2 3 #b").unwrap();
+	assert_eq!(Some(&vec!["1", "2"]), args.get_many("nums"));
+	assert_eq!(Some(&vec!["a"]), args.get_many("tags"));
+	assert_eq!(" 3 #b", args.rest);
+	assert!(cmd.get_matches("?t #a #b #c").is_none());
+
+	// Builder captures can set their own limits.
+	let cmd = check!(Command::builder()
+		.text("?add")
+		.capture(
+			builder::Capture::new("nums")
+				.quantifier(ast::Quantifier::Many1)
+				.limit(Limit::truncate(3))
+		)
+		.build());
+	assert_eq!(Some(&vec!["1", "2", "3"]), cmd.get_matches("?add 1 2 3 4").unwrap().get_many("nums"));
+}
@@ -0,0 +1,128 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2024 Taylan Gökkaya
+
+// This file is licensed under the terms of Apache-2.0 License.
+
+use crate::{
+	ast::{
+		Capture,
+		Pattern,
+		Segment,
+	},
+	parser::Quantifier,
+	Command,
+};
+
+/// How [Command::usage_with] renders a usage line.
+#[derive(Copy, Clone, Eq, PartialEq, Debug, Default, Hash)]
+pub enum UsageStyle {
+	/// Plain text, e.g. `?ban <user> [<reason>...]`.
+	#[default]
+	Plain,
+	/// Markdown suitable for chat platforms like Discord; literal text is put
+	/// in inline code and placeholders are italic, e.g. ``` `?ban` *user*
+	/// [*reason*...] ```.
+	Markdown,
+}
+
+impl Command {
+	/// Returns a conventional usage line for `self`.
+	///
+	/// Optional captures are wrapped in `[]`, repeating captures are followed by
+	/// `...`, alternatives of the `eq` filter are separated by `|` and the first
+	/// `starts` and `ends` delimiters are shown around the name of the capture.
+	/// #### Examples
+	/// ```rust
+	/// let cmd = malachi::Command::new(
+	/// 	".join [<sep?: starts('sep=')> <words+>] <flag?: eq('-v', '--verbose')>",
+	/// )?;
+	///
+	/// assert_eq!(cmd.usage(), ".join [sep=<sep>] <words>... [-v|--verbose]");
+	/// # Ok::<(), malachi::Error>(())
+	/// ```
+	pub fn usage(&self) -> String {
+		self.usage_with(UsageStyle::Plain)
+	}
+
+	/// Returns a usage line for `self` rendered with `style`.
+	///
+	/// See [Command::usage] for the format.
+	pub fn usage_with(&self, style: UsageStyle) -> String {
+		let mut parts = Vec::new();
+		for seg in &self.segments {
+			match seg {
+				Segment::Text(s) => parts.push(style.literal(s)),
+				_ => parts.extend(seg.captures().iter().map(|c| c.usage(style))),
+			}
+		}
+		parts.join(" ")
+	}
+}
+
+impl UsageStyle {
+	fn literal(self, s: &str) -> String {
+		match self {
+			Self::Plain => s.to_string(),
+			Self::Markdown if s.contains('`') => format!("`` {} ``", s),
+			Self::Markdown => format!("`{}`", s),
+		}
+	}
+
+	fn placeholder(self, name: &str) -> String {
+		match self {
+			Self::Plain => format!("<{}>", name),
+			Self::Markdown => format!("*{}*", name),
+		}
+	}
+}
+
+impl Capture {
+	fn usage(&self, style: UsageStyle) -> String {
+		let mut alts = Vec::new();
+		if self.patterns.is_empty() {
+			alts.push(style.placeholder(&self.name));
+		}
+		for p in &self.patterns {
+			for s in p.usage(&self.name, style) {
+				if !alts.contains(&s) {
+					alts.push(s);
+				}
+			}
+		}
+
+		let body = alts.join("|");
+		match self.quantifier {
+			Quantifier::Once => body,
+			Quantifier::MaybeOnce => format!("[{}]", body),
+			Quantifier::Many0 => format!("[{}...]", body),
+			Quantifier::Many1 => format!("{}...", body),
+		}
+	}
+}
+
+impl Pattern {
+	/// Returns the alternatives this pattern accepts.
+	fn usage(&self, name: &str, style: UsageStyle) -> Vec<String> {
+		let wrap = |start: Option<&String>, end: Option<&String>| {
+			let mut buf = String::new();
+			if let Some(s) = start {
+				buf.push_str(&style.literal(s));
+			}
+			buf.push_str(&style.placeholder(name));
+			if let Some(s) = end {
+				buf.push_str(&style.literal(s));
+			}
+			buf
+		};
+
+		match self {
+			Self::Eq { any_of, .. } => any_of.iter().map(|s| style.literal(s)).collect(),
+			Self::Delimited { starts, ends, .. } => vec![wrap(starts.first(), ends.first())],
+			Self::CodeBlock { .. } => {
+				let ticks = String::from("```");
+				vec![wrap(Some(&ticks), Some(&ticks))]
+			}
+			Self::Word { .. } | Self::Extract { .. } => vec![style.placeholder(name)],
+		}
+	}
+}
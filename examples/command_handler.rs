@@ -88,20 +88,23 @@ fn main() -> Result<(), malachi::Error> {
 		cmd_join,
 	)?;
 
+	// Usage lines are generated from the commands so they never go stale.
 	println!(
 		"Available commands:
-.sort [-desc] <words>
+{}
 	Sorts the given input lexicographically.
 	examples:
 	.sort foo banana wow
 	.sort -desc house tree hi sup
 
-.join [sep=?] <words>
+{}
 	Join the given words with the separator given. The default separator is `-`.
 	examples:
 	.join sep=_ snake case
 	.join kebab case
-	.join sep=:: std io stdin"
+	.join sep=:: std io stdin",
+		cmds.0[0].cmd.usage(),
+		cmds.0[1].cmd.usage(),
 	);
 
 	let stdin = io::stdin();
@@ -109,7 +112,7 @@ fn main() -> Result<(), malachi::Error> {
 	print!("> ");
 	io::stdout().flush().unwrap();
 
-	for msg in stdin.lines().filter_map(Result::ok) {
+	for msg in stdin.lines().map_while(Result::ok) {
 		cmds.execute(&msg);
 		print!("> ");
 		io::stdout().flush().unwrap();
@@ -0,0 +1,194 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2024 Taylan Gökkaya
+
+// This file is licensed under the terms of Apache-2.0 License.
+
+use malachi_core::{
+	ast::Quantifier,
+	Command,
+};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{
+	ext::IdentExt,
+	spanned::Spanned,
+	Data,
+	DeriveInput,
+	Fields,
+	GenericArgument,
+	Ident,
+	LitStr,
+	PathArguments,
+	Type,
+};
+
+/// How a field is built from its capture, decided by the type of the field.
+#[derive(Copy, Clone, Eq, PartialEq)]
+enum Kind {
+	/// Any type other than `Option` and `Vec`; the capture must match once.
+	Once,
+	/// `Option<T>`.
+	Optional,
+	/// `Vec<T>`.
+	Many,
+}
+
+struct Field<'a> {
+	ident: &'a Ident,
+	name: String,
+	name_span: proc_macro2::Span,
+	kind: Kind,
+}
+
+pub fn derive(input: &DeriveInput) -> syn::Result<TokenStream> {
+	let fields = match &input.data {
+		Data::Struct(s) => match &s.fields {
+			Fields::Named(fields) => &fields.named,
+			_ => return Err(error(input, "`FromArgs` can only be derived for structs with named fields")),
+		},
+		_ => return Err(error(input, "`FromArgs` can only be derived for structs")),
+	};
+
+	let mut src = None;
+	for attr in input.attrs.iter().filter(|a| a.path().is_ident("malachi")) {
+		attr.parse_nested_meta(|meta| {
+			if meta.path.is_ident("command") {
+				src = Some(meta.value()?.parse::<LitStr>()?);
+				Ok(())
+			} else {
+				Err(meta.error("expected `command`"))
+			}
+		})?;
+	}
+
+	let fields = fields
+		.iter()
+		.map(|f| {
+			let ident = f.ident.as_ref().expect("named fields have names");
+			let mut name = None;
+			for attr in f.attrs.iter().filter(|a| a.path().is_ident("malachi")) {
+				attr.parse_nested_meta(|meta| {
+					if meta.path.is_ident("name") {
+						name = Some(meta.value()?.parse::<LitStr>()?);
+						Ok(())
+					} else {
+						Err(meta.error("expected `name`"))
+					}
+				})?;
+			}
+
+			Ok(Field {
+				ident,
+				name_span: name.as_ref().map_or_else(|| ident.span(), LitStr::span),
+				name: name.map_or_else(|| ident.unraw().to_string(), |s| s.value()),
+				kind: kind(&f.ty),
+			})
+		})
+		.collect::<syn::Result<Vec<_>>>()?;
+
+	let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+	let ty = &input.ident;
+
+	let command_fn = src.map(|src| {
+		let errors = crate::compile(&src)
+			.and_then(|cmd| check(&cmd, &fields))
+			.err();
+		let command = crate::command(&src, errors);
+		quote! {
+			impl #impl_generics #ty #ty_generics #where_clause {
+				/// Returns the command this type is built from.
+				pub fn command() -> &'static ::malachi::Command {
+					#command
+				}
+			}
+		}
+	});
+
+	let inits = fields.iter().map(|f| {
+		let Field { ident, name, .. } = f;
+		match f.kind {
+			Kind::Once => quote! {
+				#ident: args
+					.parse_once(#name)?
+					.ok_or_else(|| ::malachi::FromArgsError::missing(#name))?
+			},
+			Kind::Optional => quote!(#ident: args.parse_once(#name)?),
+			Kind::Many => quote!(#ident: args.parse_many(#name)?),
+		}
+	});
+
+	Ok(quote! {
+		impl #impl_generics ::malachi::FromArgs for #ty #ty_generics #where_clause {
+			fn from_args(
+				args: &::malachi::Args,
+			) -> ::core::result::Result<Self, ::malachi::FromArgsError> {
+				::core::result::Result::Ok(Self {
+					#(#inits,)*
+				})
+			}
+		}
+
+		#command_fn
+	})
+}
+
+/// Checks that every field has a capture in `cmd` with a quantifier that fits
+/// the type of the field.
+fn check(cmd: &Command, fields: &[Field]) -> syn::Result<()> {
+	let mut errors = fields.iter().filter_map(|f| {
+		let msg = match (cmd.capture(&f.name).map(|c| c.quantifier), f.kind) {
+			(None, _) => format!("the command has no capture named `{}`", f.name),
+			(Some(Quantifier::Once), Kind::Once | Kind::Optional) => return None,
+			(Some(Quantifier::MaybeOnce), Kind::Optional) => return None,
+			(Some(Quantifier::Many0 | Quantifier::Many1), Kind::Many) => return None,
+			(Some(Quantifier::Once | Quantifier::MaybeOnce), Kind::Many) => format!(
+				"the capture `{}` matches at most once; the field should not be a `Vec`",
+				f.name
+			),
+			(Some(Quantifier::MaybeOnce), Kind::Once) => format!(
+				"the capture `{}` can be missing; the field should be an `Option`",
+				f.name
+			),
+			(Some(Quantifier::Many0 | Quantifier::Many1), _) => format!(
+				"the capture `{}` can match more than once; the field should be a `Vec`",
+				f.name
+			),
+		};
+		Some(syn::Error::new(f.name_span, msg))
+	});
+
+	match errors.next() {
+		None => Ok(()),
+		Some(mut e) => {
+			e.extend(errors);
+			Err(e)
+		}
+	}
+}
+
+fn kind(ty: &Type) -> Kind {
+	let Type::Path(p) = ty else {
+		return Kind::Once;
+	};
+	let Some(last) = p.path.segments.last() else {
+		return Kind::Once;
+	};
+	let PathArguments::AngleBracketed(args) = &last.arguments else {
+		return Kind::Once;
+	};
+	if p.qself.is_some() || args.args.len() != 1 || !matches!(args.args[0], GenericArgument::Type(_)) {
+		return Kind::Once;
+	}
+
+	if last.ident == "Option" {
+		Kind::Optional
+	} else if last.ident == "Vec" {
+		Kind::Many
+	} else {
+		Kind::Once
+	}
+}
+
+fn error(node: impl Spanned, msg: &str) -> syn::Error {
+	syn::Error::new(node.span(), msg)
+}
@@ -0,0 +1,157 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2024 Taylan Gökkaya
+
+// This file is licensed under the terms of Apache-2.0 License.
+
+#![allow(clippy::tabs_in_doc_comments)]
+
+//! Procedural macros for [malachi](https://docs.rs/malachi).
+//!
+//! The macros are re-exported by `malachi` with the `macros` feature, and
+//! expand to code that refers to `::malachi`.
+
+mod from_args;
+
+use malachi_core::{
+	errors::Location,
+	Command,
+};
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::{
+	parse_macro_input,
+	DeriveInput,
+	LitStr,
+};
+
+/// Compiles a command at build time and evaluates to a `&'static
+/// malachi::Command`.
+///
+/// Syntax, filter and rule errors are reported as compile errors. The command
+/// is compiled again the first time the expression is evaluated, which can not
+/// fail since `malachi` pins the version of the compiler this crate uses.
+///
+/// Every error message starts with the line and column of the error in the
+/// command. Only nightly compilers can point into a string literal, so on
+/// stable the errors underline the whole literal.
+/// #### Examples
+/// ```rust
+/// use malachi::malachi;
+///
+/// let cmd = malachi!("?ban <user> <reason*>");
+/// let args = cmd.get_matches("?ban bob spamming").unwrap();
+/// assert_eq!(Some("bob"), args.get_once("user"));
+/// ```
+///
+/// ```compile_fail
+/// // The filter `eq` requires at least one argument.
+/// let cmd = malachi::malachi!("?ban <user: eq()>");
+/// ```
+#[proc_macro]
+pub fn malachi(input: TokenStream) -> TokenStream {
+	let lit = parse_macro_input!(input as LitStr);
+	command(&lit, compile(&lit).err()).into()
+}
+
+/// Derives `malachi::FromArgs` for a struct with named fields.
+///
+/// See the documentation of `malachi::FromArgs` for how the fields are
+/// mapped to captures.
+/// #### Examples
+/// ```rust
+/// use malachi::FromArgs;
+///
+/// #[derive(FromArgs)]
+/// #[malachi(command = "?ban <user> <tags*: starts('#')> <reason?>")]
+/// struct Ban {
+/// 	user: u64,
+/// 	reason: Option<String>,
+/// 	tags: Vec<String>,
+/// }
+///
+/// let ban = Ban::command().parse::<Ban>("?ban 42 #spam #ads").unwrap();
+/// assert_eq!(42, ban.user);
+/// assert_eq!(None, ban.reason);
+/// assert_eq!(vec!["spam", "ads"], ban.tags);
+/// ```
+///
+/// ```compile_fail
+/// #[derive(malachi::FromArgs)]
+/// #[malachi(command = "?ban <user>")]
+/// struct Ban {
+/// 	// The command has no capture named `id`.
+/// 	id: u64,
+/// }
+/// ```
+#[proc_macro_derive(FromArgs, attributes(malachi))]
+pub fn derive_from_args(input: TokenStream) -> TokenStream {
+	let input = parse_macro_input!(input as DeriveInput);
+	from_args::derive(&input)
+		.unwrap_or_else(syn::Error::into_compile_error)
+		.into()
+}
+
+/// Expands to an expression evaluating to a lazily compiled `&'static
+/// malachi::Command`, preceded by `errors` if there are any.
+fn command(lit: &LitStr, errors: Option<syn::Error>) -> proc_macro2::TokenStream {
+	// The errors are emitted as statements before the usual expansion so that
+	// they are the only errors reported.
+	let errors = errors.map(syn::Error::into_compile_error);
+
+	quote! {{
+		#errors
+		static COMMAND: ::std::sync::OnceLock<::malachi::Command> = ::std::sync::OnceLock::new();
+		COMMAND.get_or_init(|| {
+			::malachi::Command::new(#lit).expect("the command was checked at compile time")
+		})
+	}}
+}
+
+/// Compiles the command in `lit`, converting every error into one
+/// [syn::Error].
+fn compile(lit: &LitStr) -> syn::Result<Command> {
+	let diagnostics = match Command::compile(&lit.value()) {
+		Ok(cmd) => return Ok(cmd),
+		Err(ds) => ds,
+	};
+
+	let mut errors = diagnostics.into_iter().map(|d| {
+		let mut msg = String::new();
+		if let Some(loc) = &d.location {
+			msg = format!("{}: ", loc);
+		}
+		msg.push_str(&d.message);
+		if let Some(hint) = &d.hint {
+			msg = format!("{}\nhelp: {}", msg, hint);
+		}
+		syn::Error::new(span_of(lit, d.location.as_ref()), msg)
+	});
+
+	let mut e = errors.next().expect("compile failed without any errors");
+	e.extend(errors);
+	Err(e)
+}
+
+/// Returns the span of `loc` inside `lit`, or the span of the whole literal
+/// if the compiler can not point into literals or `lit` has escapes.
+///
+/// `Literal::subspan` always returns `None` on stable compilers, so this only
+/// narrows the span on nightly.
+fn span_of(lit: &LitStr, loc: Option<&Location>) -> Span {
+	let inner = |loc: &Location| {
+		let token = lit.token();
+		let s = token.to_string();
+		let quote = s.find('"')?;
+		// Raw strings have as many `#` after the closing quote as before the
+		// opening one.
+		let hashes = quote.saturating_sub(1);
+		if s.get(quote + 1..s.len() - 1 - hashes)? != lit.value() {
+			return None;
+		}
+		let start = quote + 1 + loc.span.start;
+		token.subspan(start..start + loc.span.len())
+	};
+
+	loc.and_then(inner).unwrap_or_else(|| lit.span())
+}
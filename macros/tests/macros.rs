@@ -0,0 +1,58 @@
+// SPDX-License-Identifier: Apache-2.0
+// Copyright 2024 Taylan Gökkaya
+
+use malachi::{
+	malachi,
+	FromArgs,
+	FromArgsError,
+};
+
+#[test]
+fn command() {
+	let cmd = malachi!("?ban <user> <reason*>");
+	let args = cmd.get_matches("?ban bob spam spam").unwrap();
+	assert_eq!(Some("bob"), args.get_once("user"));
+	assert_eq!(Some(&vec!["spam", "spam"]), args.get_many("reason"));
+
+	// The command is only compiled once.
+	let get = || malachi!(r#"?say <msg: starts('"'), ends('"')>"#) as *const _;
+	assert_eq!(get(), get());
+}
+
+#[derive(FromArgs, Debug)]
+#[malachi(command = "?ban <user> <tags*: starts('#')> <reason?>")]
+struct Ban {
+	user: u64,
+	#[malachi(name = "reason")]
+	why: Option<String>,
+	tags: Vec<String>,
+}
+
+#[test]
+fn from_args() {
+	let ban = Ban::command().parse::<Ban>("?ban 42 #a #b").unwrap();
+	assert_eq!(42, ban.user);
+	assert_eq!(None, ban.why);
+	assert_eq!(vec!["a", "b"], ban.tags);
+
+	let ban = Ban::command().parse::<Ban>("?ban 7 spam").unwrap();
+	assert_eq!(Some("spam"), ban.why.as_deref());
+
+	match Ban::command().parse::<Ban>("?ban bob") {
+		Err(FromArgsError::Invalid { capture, value, .. }) => {
+			assert_eq!("user", capture);
+			assert_eq!("bob", value);
+		}
+		x => panic!("expected an invalid value error, got {x:?}"),
+	}
+	assert!(matches!(
+		Ban::command().parse::<Ban>("?kick 42"),
+		Err(FromArgsError::NoMatch)
+	));
+}
+
+#[test]
+fn ui() {
+	let t = trybuild::TestCases::new();
+	t.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,7 @@
+use malachi::malachi;
+
+fn main() {
+	let _ = malachi!("?ban <user");
+	let _ = malachi!("?ban <user: eq()> <x: nope()>");
+	let _ = malachi!("?ban <a*> <b*>");
+}
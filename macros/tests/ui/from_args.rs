@@ -0,0 +1,19 @@
+use malachi::FromArgs;
+
+#[derive(FromArgs)]
+#[malachi(command = "?ban <user> <tags*: starts('#')> <reason?>")]
+struct Ban {
+	id: u64,
+	reason: String,
+	tags: Option<String>,
+	#[malachi(name = "user")]
+	users: Vec<String>,
+}
+
+#[derive(FromArgs)]
+#[malachi(command = "?ban <user")]
+struct Broken {
+	user: String,
+}
+
+fn main() {}